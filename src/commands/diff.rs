@@ -0,0 +1,26 @@
+use std::io::{IsTerminal, Write};
+
+use crate::{commands::monitor::source, context::Context, session::Session};
+
+pub async fn main(context: Context, old_id: u64, new_id: u64) -> anyhow::Result<()> {
+    let session = Session::load()
+        .ok_or_else(|| anyhow::anyhow!("Failed to create agent, have you logged in?"))?;
+
+    let old_url = source::detail_url(&context.contest_url, old_id);
+    let new_url = source::detail_url(&context.contest_url, new_id);
+    let (old, new) = tokio::try_join!(
+        source::fetch(&session, &old_url),
+        source::fetch(&session, &new_url)
+    )?;
+    let mut stdout = std::io::stdout().lock();
+    let color = stdout.is_terminal();
+    for line in source::unified_diff(&old_id.to_string(), &old, &new_id.to_string(), &new) {
+        if color {
+            writeln!(stdout, "{}", source::colorize(&line))?;
+        } else {
+            writeln!(stdout, "{}", line)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use std::{io::Write, path::PathBuf};
+
+use crate::{
+    commands::monitor::export, config::ExportFormat, context::Context, info, session::Session,
+};
+
+pub struct Options {
+    pub format: ExportFormat,
+    /// The file to write to, or stdout if not specified.
+    pub output: Option<PathBuf>,
+}
+
+pub async fn main(context: Context, options: Options) -> anyhow::Result<()> {
+    let session = Session::load()
+        .ok_or_else(|| anyhow::anyhow!("Failed to create agent, have you logged in?"))?;
+
+    let records =
+        export::fetch_records(&session, &context.contest_url, context.task.as_deref()).await?;
+    let text = export::render(&records, options.format)?;
+    match options.output {
+        Some(output) => {
+            fs_err::write(&output, text)?;
+            info!(
+                "Exported {} submissions to {}",
+                records.len(),
+                output.display()
+            );
+        }
+        None => std::io::stdout().write_all(text.as_bytes())?,
+    }
+
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use crate::{
+    commands::monitor::{self, export, source},
+    context::Context,
+    info,
+    session::Session,
+};
+
+pub struct Options {
+    /// Only download accepted submissions.
+    pub accepted_only: bool,
+    /// Only download the latest submission to each task.
+    pub latest: bool,
+    /// The directory the contest directory is created in.
+    pub output: PathBuf,
+}
+
+pub async fn main(context: Context, options: Options) -> anyhow::Result<()> {
+    let session = Session::load()
+        .ok_or_else(|| anyhow::anyhow!("Failed to create agent, have you logged in?"))?;
+
+    let records =
+        export::fetch_records(&session, &context.contest_url, context.task.as_deref()).await?;
+    // Submissions still being judged would be saved under a verdict that is about to change.
+    let mut records = records
+        .into_iter()
+        .filter(|record| !record.pending)
+        .filter(|record| !options.accepted_only || record.status == "AC")
+        .collect::<Vec<_>>();
+    if options.latest {
+        let mut latest = HashMap::new();
+        for record in records {
+            latest.insert(record.task.clone(), record);
+        }
+        records = latest.into_values().collect();
+        records.sort_by_key(|record| record.id);
+    }
+
+    let contest_dir = options
+        .output
+        .join(monitor::contest_id(&context.contest_url));
+    let (mut downloaded, mut skipped) = (0, 0);
+    for record in &records {
+        let dir = contest_dir.join(&record.task);
+        let path = dir.join(format!(
+            "{}_{}.{}",
+            record.id,
+            record.status,
+            source::extension(&record.language)
+        ));
+        if path.exists() {
+            skipped += 1;
+            continue;
+        }
+        if downloaded > 0 {
+            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        }
+        let code = source::fetch(&session, &record.url).await?;
+        fs_err::create_dir_all(&dir)?;
+        fs_err::write(&path, code)?;
+        info!("Downloaded {}", path.display());
+        downloaded += 1;
+    }
+    info!(
+        "Downloaded {} submissions, skipped {} existing files",
+        downloaded, skipped
+    );
+
+    Ok(())
+}
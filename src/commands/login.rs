@@ -1,73 +1,96 @@
-use std::{collections::HashMap, sync::Arc};
+use std::io::{BufRead, IsTerminal};
 
 use anyhow::ensure;
 
-use crate::{debug, info, question, store};
+use crate::{credentials, info, question, session::Session, store};
+
+const USERNAME_ENV: &str = "ATCODER_USERNAME";
+const PASSWORD_ENV: &str = "ATCODER_PASSWORD";
+const REVEL_SESSION_ENV: &str = "ATCODER_REVEL_SESSION";
+
+pub struct Options {
+    pub username: Option<String>,
+    pub password_stdin: bool,
+    pub revel_session: Option<String>,
+    pub remember: bool,
+}
+
+pub async fn main(options: Options) -> anyhow::Result<()> {
+    // Options for logging in with a password take precedence over the environment.
+    let password_login = options.username.is_some() || options.password_stdin || options.remember;
+    let revel_session = options.revel_session.or_else(|| {
+        std::env::var(REVEL_SESSION_ENV)
+            .ok()
+            .filter(|_| !password_login)
+    });
+    if let Some(revel_session) = revel_session {
+        ensure!(
+            !options.remember,
+            "--remember cannot be used when importing a session"
+        );
+        return import_session(&revel_session).await;
+    }
+
+    let username = match options
+        .username
+        .or_else(|| std::env::var(USERNAME_ENV).ok())
+    {
+        Some(username) => username,
+        // Without a terminal the username cannot be asked, and stdin may hold the password.
+        None if options.password_stdin || !std::io::stdin().is_terminal() => {
+            anyhow::bail!("No username given, pass --username or set {}", USERNAME_ENV)
+        }
+        None => dialoguer::Input::<String>::new()
+            .with_prompt(question!("Enter your username"))
+            .interact()?,
+    };
+    let password = if options.password_stdin {
+        let mut password = String::new();
+        std::io::stdin().lock().read_line(&mut password)?;
+        password.trim_end_matches(['\r', '\n']).to_string()
+    } else if let Ok(password) = std::env::var(PASSWORD_ENV) {
+        password
+    } else {
+        dialoguer::Password::new()
+            .with_prompt(question!("Enter your password"))
+            .interact()?
+    };
+    ensure!(!password.is_empty(), "Password is empty");
 
-pub async fn main() -> anyhow::Result<()> {
-    let username = dialoguer::Input::<String>::new()
-        .with_prompt(question!("Enter your username"))
-        .interact()?;
-    let password = dialoguer::Password::new()
-        .with_prompt(question!("Enter your password"))
-        .interact()?;
     let cookie_path = store::get_cookie_path();
     let _ = fs_err::remove_file(&cookie_path);
 
     info!("Logging in...");
 
-    let cookie_store = reqwest_cookie_store::CookieStore::default();
-    let cookie_store = reqwest_cookie_store::CookieStoreMutex::new(cookie_store);
-    let cookie_store = Arc::new(cookie_store);
-    let client = reqwest::Client::builder()
-        .user_agent("AtCoderJudgeMonitor/0.1")
-        .cookie_store(true)
-        .cookie_provider(Arc::clone(&cookie_store))
-        .build()?;
-    let login_html: String = client
-        .get("https://atcoder.jp/login")
-        .send()
-        .await?
-        .error_for_status()?
-        .text()
-        .await?;
-
-    let login_html = scraper::Html::parse_document(&login_html);
-    let csrf_token = login_html
-        .select(&scraper::Selector::parse("input[name=csrf_token]").unwrap())
-        .next()
-        .unwrap()
-        .value()
-        .attr("value")
-        .unwrap();
-    debug!("CSRF token: {}", csrf_token);
-
-    let mut params = HashMap::new();
-    params.insert("username", username.clone());
-    params.insert("password", password.clone());
-    params.insert("csrf_token", csrf_token.to_string());
-    let login_result = client
-        .post("https://atcoder.jp/login")
-        .header("Referer", "https://atcoder.jp/login")
-        .form(&params)
-        .send()
-        .await?
-        .error_for_status()?
-        .text()
-        .await?;
-
-    ensure!(
-        login_result.contains(format!(r#"var userScreenName = "{}";"#, username).as_str()),
-        "Failed to login"
-    );
+    let session = Session::new();
+    session.login(&username, &password).await?;
     info!("Logged in as {}", &username);
 
-    let cookies = cookie_store.lock().unwrap();
+    session.save()?;
 
-    cookie_store::serde::json::save(&cookies, &mut fs_err::File::create(&cookie_path)?)
-        .map_err(|err| anyhow::anyhow!("Failed to save cookies: {}", err))?;
+    if options.remember {
+        let backend = credentials::save(&credentials::Credentials { username, password }).await?;
+        info!("Credentials saved to {} for automatic re-login", backend);
+    } else {
+        credentials::delete().await;
+    }
+
+    Ok(())
+}
+
+async fn import_session(revel_session: &str) -> anyhow::Result<()> {
+    info!("Importing session...");
+
+    let session = Session::new();
+    session.import_revel_session(revel_session)?;
+    let username = session
+        .current_user()
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("The imported session is not logged in"))?;
+    info!("Logged in as {}", username);
 
-    debug!("Cookies saved to {:?}", cookie_path);
+    session.save()?;
+    credentials::delete().await;
 
     Ok(())
 }
@@ -0,0 +1,17 @@
+use crate::{credentials, info, session::Session, store, warn};
+
+pub async fn main() -> anyhow::Result<()> {
+    let cookie_path = store::get_cookie_path();
+    if let Some(session) = Session::load() {
+        info!("Logging out...");
+        if let Err(err) = session.logout().await {
+            warn!("Failed to invalidate the session: {}", err);
+        }
+    }
+
+    let _ = fs_err::remove_file(&cookie_path);
+    credentials::delete().await;
+    info!("Logged out");
+
+    Ok(())
+}
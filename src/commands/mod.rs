@@ -1,2 +1,8 @@
+pub mod diff;
+pub mod export;
+pub mod fetch_sources;
 pub mod login;
+pub mod logout;
 pub mod monitor;
+pub mod webhook_test;
+pub mod whoami;
@@ -0,0 +1,173 @@
+use console::Alignment;
+
+use super::{summary::ScoreTrend, Submission};
+use crate::config::Column;
+
+struct Spec {
+    min_width: usize,
+    /// Flexible columns grow up to this width when there is room.
+    max_width: usize,
+    /// Columns with a lower priority are dropped first on narrow terminals.
+    priority: u8,
+    alignment: Alignment,
+}
+
+fn spec(column: Column, heuristic: bool) -> Spec {
+    let (min_width, max_width, priority, alignment) = match column {
+        // Heuristic scores are large and followed by the improvement over the previous best.
+        Column::Score if heuristic => (13, 24, 10, Alignment::Right),
+        Column::Status => (3, 3, 9, Alignment::Right),
+        Column::Problem => (12, 30, 8, Alignment::Left),
+        Column::Score => (7, 7, 7, Alignment::Right),
+        Column::ExecutionTime => (8, 8, 6, Alignment::Right),
+        Column::Time => (19, 19, 5, Alignment::Left),
+        Column::Memory => (10, 10, 4, Alignment::Right),
+        Column::Language => (8, 24, 3, Alignment::Left),
+        Column::CodeSize => (10, 10, 2, Alignment::Right),
+        Column::Id => (9, 9, 1, Alignment::Right),
+        Column::Elapsed => (7, 9, 5, Alignment::Right),
+    };
+    Spec {
+        min_width,
+        max_width,
+        priority,
+        alignment,
+    }
+}
+
+/// Returns the separator drawn after `column`.
+pub fn separator(column: Column) -> &'static str {
+    match column {
+        Column::Status => ": ",
+        _ => " | ",
+    }
+}
+
+/// Chooses the columns that fit in `width` and how wide each of them is.
+pub fn layout(columns: &[Column], width: usize, heuristic: bool) -> Vec<(Column, usize)> {
+    let spec = |column| spec(column, heuristic);
+    let mut chosen = columns.to_vec();
+    let needed = |chosen: &[Column]| {
+        let cells = chosen
+            .iter()
+            .map(|&column| spec(column).min_width)
+            .sum::<usize>();
+        let separators = chosen
+            .iter()
+            .rev()
+            .skip(1)
+            .map(|&column| separator(column).len())
+            .sum::<usize>();
+        cells + separators
+    };
+    while chosen.len() > 1 && needed(&chosen) > width {
+        let (index, _) = chosen
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, &column)| spec(column).priority)
+            .unwrap();
+        chosen.remove(index);
+    }
+
+    let mut spare = width.saturating_sub(needed(&chosen));
+    let mut widths = chosen
+        .iter()
+        .map(|&column| spec(column).min_width)
+        .collect::<Vec<_>>();
+    let mut by_priority = (0..chosen.len()).collect::<Vec<_>>();
+    by_priority.sort_by_key(|&index| std::cmp::Reverse(spec(chosen[index]).priority));
+    for index in by_priority {
+        let spec = spec(chosen[index]);
+        let extra = spare.min(spec.max_width - spec.min_width);
+        widths[index] += extra;
+        spare -= extra;
+    }
+
+    chosen.into_iter().zip(widths).collect()
+}
+
+/// What cells need to know about the contest besides the submission itself.
+pub struct CellContext<'a> {
+    /// The score trend in heuristic contests.
+    pub trend: Option<&'a ScoreTrend>,
+    pub start_time: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Renders a cell without styles, padded or truncated to `width`.
+pub fn render_cell(
+    column: Column,
+    id: u64,
+    submission: &Submission,
+    width: usize,
+    context: &CellContext,
+) -> String {
+    let text = match column {
+        Column::Status => submission.status.to_string(),
+        Column::Time => {
+            let local_time = submission.time.with_timezone(&chrono::Local);
+            local_time.format("%Y-%m-%d %H:%M:%S").to_string()
+        }
+        Column::Problem => submission.problem.clone(),
+        Column::Score => match context.trend {
+            Some(trend) => {
+                let score = super::summary::format_score(submission.score);
+                match trend.improvement_of(id) {
+                    Some(Some(improvement)) => format!(
+                        "{} ({})",
+                        score,
+                        super::summary::format_improvement(improvement)
+                    ),
+                    Some(None) => format!("{} (best)", score),
+                    None => score,
+                }
+            }
+            None => format!("{}pts", submission.score),
+        },
+        Column::ExecutionTime => submission.execution_time.clone().unwrap_or_default(),
+        Column::Memory => submission.memory.clone().unwrap_or_default(),
+        Column::CodeSize => submission.code_size.clone(),
+        Column::Language => submission.language.clone(),
+        Column::Id => id.to_string(),
+        Column::Elapsed => match context.start_time {
+            Some(start_time) if submission.time < start_time => {
+                format!(
+                    "-{}",
+                    super::summary::format_elapsed(start_time - submission.time)
+                )
+            }
+            Some(start_time) => {
+                format!(
+                    "+{}",
+                    super::summary::format_elapsed(submission.time - start_time)
+                )
+            }
+            None => String::new(),
+        },
+    };
+    fit(
+        &text,
+        width,
+        spec(column, context.trend.is_some()).alignment,
+    )
+}
+
+/// Pads `text` to `width`, truncating it only if it does not fit.
+pub fn fit(text: &str, width: usize, alignment: Alignment) -> String {
+    if console::measure_text_width(text) <= width {
+        console::pad_str(text, width, alignment, None).to_string()
+    } else {
+        truncate(text, width)
+    }
+}
+
+/// Truncates `text` with an ellipsis if it is wider than `width`.
+/// `console::truncate_str` alone also cuts text that fits within the last three columns.
+pub fn truncate(text: &str, width: usize) -> String {
+    if console::measure_text_width(text) <= width {
+        text.to_string()
+    } else if width >= 3 {
+        console::truncate_str(text, width, "...").to_string()
+    } else {
+        console::truncate_str(text, width, "").to_string()
+    }
+}
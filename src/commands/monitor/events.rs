@@ -0,0 +1,43 @@
+use tokio::sync::broadcast;
+
+use super::{Submission, SubmissionStatus, SUBMISSIONS};
+
+/// A change of the verdict of a submission, found by `poll`.
+#[derive(Debug, Clone)]
+pub struct Change {
+    pub id: u64,
+    pub submission: Submission,
+    /// `None` for a submission that was not seen before.
+    pub previous: Option<SubmissionStatus>,
+}
+
+static CHANGES: std::sync::LazyLock<broadcast::Sender<Change>> =
+    std::sync::LazyLock::new(|| broadcast::channel(256).0);
+
+pub fn subscribe() -> broadcast::Receiver<Change> {
+    CHANGES.subscribe()
+}
+
+/// Merges polled submissions into `SUBMISSIONS` and publishes the verdict changes.
+/// Nothing is published for the first poll, as those submissions were made before monitoring.
+pub async fn merge(submissions: indexmap::IndexMap<u64, Submission>, first_poll: bool) {
+    let mut changes = vec![];
+    {
+        let mut locked = SUBMISSIONS.lock().await;
+        for (id, submission) in submissions {
+            let previous = locked.get(&id).map(|previous| previous.status);
+            if !first_poll && previous != Some(submission.status) {
+                changes.push(Change {
+                    id,
+                    submission: submission.clone(),
+                    previous,
+                });
+            }
+            locked.insert(id, submission);
+        }
+    }
+    for change in changes {
+        // Sending only fails when nobody is listening.
+        let _ = CHANGES.send(change);
+    }
+}
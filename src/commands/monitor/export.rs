@@ -0,0 +1,164 @@
+use std::path::PathBuf;
+
+use super::{record::Record, MessageKind, Submission, SUBMISSIONS};
+use crate::{config::ExportFormat, session::Session};
+
+/// Where the export action writes, set when the monitor starts.
+pub static TARGET: std::sync::OnceLock<(PathBuf, ExportFormat)> = std::sync::OnceLock::new();
+
+const HEADERS: [&str; 10] = [
+    "id",
+    "time",
+    "problem",
+    "status",
+    "score",
+    "language",
+    "execution_time_ms",
+    "memory_kib",
+    "code_size_bytes",
+    "url",
+];
+
+/// Returns the fields of the record in the order of `HEADERS`.
+fn fields(record: &Record, time: String) -> [String; 10] {
+    let optional = |value: Option<u64>| value.map(|value| value.to_string()).unwrap_or_default();
+    [
+        record.id.to_string(),
+        time,
+        record.problem.clone(),
+        record.status.clone(),
+        record.score.to_string(),
+        record.language.clone(),
+        optional(record.execution_time_ms),
+        optional(record.memory_kib),
+        optional(record.code_size_bytes),
+        record.url.clone(),
+    ]
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn markdown_field(field: &str) -> String {
+    field.replace('|', "\\|")
+}
+
+pub fn render(records: &[Record], format: ExportFormat) -> anyhow::Result<String> {
+    Ok(match format {
+        ExportFormat::Csv => {
+            let mut text = HEADERS.join(",") + "\n";
+            for record in records {
+                let fields = fields(record, record.time.to_rfc3339());
+                text += &fields.map(|field| csv_field(&field)).join(",");
+                text += "\n";
+            }
+            text
+        }
+        ExportFormat::Json => serde_json::to_string_pretty(records)? + "\n",
+        ExportFormat::Markdown => {
+            let mut text = format!("| {} |\n", HEADERS.join(" | "));
+            text += &format!("|{}\n", "---|".repeat(HEADERS.len()));
+            for record in records {
+                let time = record
+                    .time
+                    .with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string();
+                let fields = fields(record, time);
+                text += &format!(
+                    "| {} |\n",
+                    fields.map(|field| markdown_field(&field)).join(" | ")
+                );
+            }
+            text
+        }
+    })
+}
+
+/// Converts the submissions into records, oldest first.
+fn records(submissions: &indexmap::IndexMap<u64, Submission>) -> Vec<Record> {
+    let mut records = submissions
+        .iter()
+        .map(|(id, submission)| Record::new(*id, submission))
+        .collect::<Vec<_>>();
+    records.sort_by_key(|record| record.id);
+    records
+}
+
+/// Fetches every page of the submissions.
+#[cfg(not(feature = "dummy-submissions"))]
+pub(super) async fn fetch_all(
+    session: &Session,
+    submissions_url: &str,
+) -> anyhow::Result<indexmap::IndexMap<u64, Submission>> {
+    let separator = if submissions_url.contains('?') {
+        '&'
+    } else {
+        '?'
+    };
+    let mut submissions = indexmap::IndexMap::new();
+    for page in 1.. {
+        let url = format!("{}{}page={}", submissions_url, separator, page);
+        let page_submissions = super::fetch_submissions(session, &url).await?;
+        // Pages past the last one have no new submissions.
+        let before = submissions.len();
+        submissions.extend(page_submissions);
+        if submissions.len() == before {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    }
+    Ok(submissions)
+}
+
+#[cfg(feature = "dummy-submissions")]
+pub(super) async fn fetch_all(
+    _session: &Session,
+    _submissions_url: &str,
+) -> anyhow::Result<indexmap::IndexMap<u64, Submission>> {
+    Ok(super::dummy_submissions(chrono::Utc::now(), 1))
+}
+
+/// Fetches every own submission in the contest, optionally only to the task.
+pub async fn fetch_records(
+    session: &Session,
+    contest_url: &str,
+    task: Option<&str>,
+) -> anyhow::Result<Vec<Record>> {
+    let submissions = fetch_all(session, &super::submissions_url(contest_url, task)).await?;
+    Ok(records(&submissions))
+}
+
+/// Writes the monitored submissions to the export target.
+pub async fn export_current() {
+    let Some((path, format)) = TARGET.get() else {
+        return;
+    };
+    let records = records(&*SUBMISSIONS.lock().await);
+    let result = render(&records, *format).and_then(|text| Ok(fs_err::write(path, text)?));
+    match result {
+        Ok(()) => {
+            super::message(
+                MessageKind::Info,
+                format!(
+                    "Exported {} submissions to {}",
+                    records.len(),
+                    path.display()
+                ),
+            )
+            .await
+        }
+        Err(err) => {
+            super::message(
+                MessageKind::Error,
+                format!("Failed to export submissions: {}", err),
+            )
+            .await
+        }
+    }
+}
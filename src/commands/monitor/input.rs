@@ -0,0 +1,401 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Key {
+    Char(char),
+    Ctrl(char),
+    Alt(char),
+    Up,
+    Down,
+    Left,
+    Right,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+    Insert,
+    Delete,
+    Enter,
+    Tab,
+    BackTab,
+    Backspace,
+    Esc,
+    F(u8),
+}
+
+impl std::str::FromStr for Key {
+    type Err = anyhow::Error;
+
+    /// Parses a key name used in the config, e.g. `q`, `G`, `ctrl-r`, `alt-x`, `pagedown` or `f5`.
+    fn from_str(name: &str) -> anyhow::Result<Key> {
+        let mut chars = name.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Ok(Key::Char(c));
+        }
+
+        let lowercase = name.to_ascii_lowercase();
+        let single_char = |rest: &str| {
+            let mut chars = rest.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(c),
+                _ => Err(anyhow::anyhow!("Invalid key: {:?}", name)),
+            }
+        };
+        if let Some(rest) = lowercase.strip_prefix("ctrl-") {
+            let c = single_char(rest)?;
+            anyhow::ensure!(c.is_ascii_lowercase(), "Invalid key: {:?}", name);
+            return Ok(Key::Ctrl(c));
+        }
+        if let Some(rest) = name.get(4..).filter(|_| lowercase.starts_with("alt-")) {
+            return Ok(Key::Alt(single_char(rest)?));
+        }
+        if let Some(number) = lowercase
+            .strip_prefix('f')
+            .and_then(|number| number.parse::<u8>().ok())
+        {
+            anyhow::ensure!((1..=12).contains(&number), "Invalid key: {:?}", name);
+            return Ok(Key::F(number));
+        }
+        Ok(match lowercase.as_str() {
+            "space" => Key::Char(' '),
+            "up" => Key::Up,
+            "down" => Key::Down,
+            "left" => Key::Left,
+            "right" => Key::Right,
+            "pageup" => Key::PageUp,
+            "pagedown" => Key::PageDown,
+            "home" => Key::Home,
+            "end" => Key::End,
+            "insert" => Key::Insert,
+            "delete" => Key::Delete,
+            "enter" => Key::Enter,
+            "tab" => Key::Tab,
+            "backtab" => Key::BackTab,
+            "backspace" => Key::Backspace,
+            "esc" => Key::Esc,
+            _ => anyhow::bail!("Invalid key: {:?}", name),
+        })
+    }
+}
+
+impl std::fmt::Display for Key {
+    /// Formats the key as shown in the footer and the help.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Key::Char(' ') => write!(f, "Space"),
+            Key::Char(c) => write!(f, "{}", c),
+            Key::Ctrl(c) => write!(f, "Ctrl-{}", c.to_ascii_uppercase()),
+            Key::Alt(c) => write!(f, "Alt-{}", c),
+            Key::Up => write!(f, "↑"),
+            Key::Down => write!(f, "↓"),
+            Key::Left => write!(f, "←"),
+            Key::Right => write!(f, "→"),
+            Key::PageUp => write!(f, "PgUp"),
+            Key::PageDown => write!(f, "PgDn"),
+            Key::Home => write!(f, "Home"),
+            Key::End => write!(f, "End"),
+            Key::Insert => write!(f, "Ins"),
+            Key::Delete => write!(f, "Del"),
+            Key::Enter => write!(f, "Enter"),
+            Key::Tab => write!(f, "Tab"),
+            Key::BackTab => write!(f, "Shift-Tab"),
+            Key::Backspace => write!(f, "Backspace"),
+            Key::Esc => write!(f, "Esc"),
+            Key::F(number) => write!(f, "F{}", number),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right,
+}
+
+/// A mouse event at a 1-based (column, row) position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mouse {
+    Press(MouseButton, u16, u16),
+    Release(u16, u16),
+    WheelUp(u16, u16),
+    WheelDown(u16, u16),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    Key(Key),
+    Mouse(Mouse),
+}
+
+/// The result of decoding the start of the input.
+enum Decoded {
+    /// An event, or `None` for a sequence that is ignored, and the number of bytes consumed.
+    Complete(Option<Event>, usize),
+    /// The input ends in the middle of a sequence.
+    Incomplete,
+}
+
+/// Decodes bytes read from stdin into events.
+/// A sequence split across reads is kept until the rest of it arrives.
+#[derive(Default)]
+pub struct Parser {
+    pending: Vec<u8>,
+}
+
+impl Parser {
+    pub fn feed(&mut self, input: &[u8]) -> Vec<Event> {
+        self.pending.extend_from_slice(input);
+
+        let mut events = vec![];
+        let mut position = 0;
+        while position < self.pending.len() {
+            match decode(&self.pending[position..]) {
+                Decoded::Complete(event, consumed) => {
+                    events.extend(event);
+                    position += consumed;
+                }
+                Decoded::Incomplete => break,
+            }
+        }
+        self.pending.drain(..position);
+        events
+    }
+}
+
+fn decode(input: &[u8]) -> Decoded {
+    let key = |key, consumed| Decoded::Complete(Some(Event::Key(key)), consumed);
+    match input[0] {
+        0x1b => match input.get(1) {
+            // Terminals send sequences at once, so an escape at the end of a read is the Esc key.
+            None => key(Key::Esc, 1),
+            Some(b'[') => decode_csi(input),
+            Some(b'O') => match input.get(2) {
+                None => Decoded::Incomplete,
+                Some(&final_byte) => {
+                    let decoded = match final_byte {
+                        b'A' => Some(Key::Up),
+                        b'B' => Some(Key::Down),
+                        b'C' => Some(Key::Right),
+                        b'D' => Some(Key::Left),
+                        b'H' => Some(Key::Home),
+                        b'F' => Some(Key::End),
+                        b'P'..=b'S' => Some(Key::F(final_byte - b'P' + 1)),
+                        _ => None,
+                    };
+                    Decoded::Complete(decoded.map(Event::Key), 3)
+                }
+            },
+            Some(0x1b) => key(Key::Esc, 1),
+            Some(_) => match decode_char(&input[1..]) {
+                Decoded::Complete(Some(Event::Key(Key::Char(c))), consumed) => {
+                    key(Key::Alt(c), consumed + 1)
+                }
+                Decoded::Complete(_, consumed) => Decoded::Complete(None, consumed + 1),
+                Decoded::Incomplete => Decoded::Incomplete,
+            },
+        },
+        b'\r' | b'\n' => key(Key::Enter, 1),
+        b'\t' => key(Key::Tab, 1),
+        0x7f | 0x08 => key(Key::Backspace, 1),
+        byte @ 0x01..=0x1a => key(Key::Ctrl((byte - 1 + b'a') as char), 1),
+        0x00..=0x1f => Decoded::Complete(None, 1),
+        _ => decode_char(input),
+    }
+}
+
+fn decode_char(input: &[u8]) -> Decoded {
+    let length = match input[0] {
+        0x00..=0x7f => 1,
+        0xc0..=0xdf => 2,
+        0xe0..=0xef => 3,
+        0xf0..=0xf7 => 4,
+        _ => return Decoded::Complete(None, 1),
+    };
+    if input.len() < length {
+        return Decoded::Incomplete;
+    }
+    match std::str::from_utf8(&input[..length]) {
+        Ok(c) => Decoded::Complete(c.chars().next().map(|c| Event::Key(Key::Char(c))), length),
+        Err(_) => Decoded::Complete(None, 1),
+    }
+}
+
+/// Decodes a control sequence starting with `ESC [`.
+fn decode_csi(input: &[u8]) -> Decoded {
+    // X10 mouse encoding: ESC [ M Cb Cx Cy
+    if input.get(2) == Some(&b'M') {
+        let [cb, cx, cy] = match input.get(3..6) {
+            Some(&[cb, cx, cy]) => [cb, cx, cy],
+            _ => return Decoded::Incomplete,
+        };
+        let (cx, cy) = (cx.saturating_sub(32) as u16, cy.saturating_sub(32) as u16);
+        let mouse = decode_mouse(cb.saturating_sub(32) as u16, cx, cy, true);
+        return Decoded::Complete(mouse.map(Event::Mouse), 6);
+    }
+
+    let Some(length) = input[2..]
+        .iter()
+        .position(|byte| (0x40..=0x7e).contains(byte))
+    else {
+        return Decoded::Incomplete;
+    };
+    let consumed = length + 3;
+    let params = std::str::from_utf8(&input[2..length + 2]).unwrap_or_default();
+    let final_byte = input[length + 2];
+
+    // SGR mouse encoding: ESC [ < Cb ; Cx ; Cy (M or m)
+    if let Some(params) = params.strip_prefix('<') {
+        let numbers = params
+            .split(';')
+            .map(|number| number.parse::<u16>().unwrap_or_default())
+            .collect::<Vec<_>>();
+        let mouse = match numbers[..] {
+            [cb, cx, cy] => decode_mouse(cb, cx, cy, final_byte == b'M'),
+            _ => None,
+        };
+        return Decoded::Complete(mouse.map(Event::Mouse), consumed);
+    }
+
+    let first_param = params
+        .split(';')
+        .next()
+        .and_then(|param| param.parse::<u8>().ok());
+    let decoded = match final_byte {
+        b'A' => Some(Key::Up),
+        b'B' => Some(Key::Down),
+        b'C' => Some(Key::Right),
+        b'D' => Some(Key::Left),
+        b'H' => Some(Key::Home),
+        b'F' => Some(Key::End),
+        b'Z' => Some(Key::BackTab),
+        b'~' => match first_param {
+            Some(1 | 7) => Some(Key::Home),
+            Some(2) => Some(Key::Insert),
+            Some(3) => Some(Key::Delete),
+            Some(4 | 8) => Some(Key::End),
+            Some(5) => Some(Key::PageUp),
+            Some(6) => Some(Key::PageDown),
+            Some(number @ 11..=15) => Some(Key::F(number - 10)),
+            Some(number @ 17..=21) => Some(Key::F(number - 11)),
+            Some(number @ 23..=24) => Some(Key::F(number - 12)),
+            _ => None,
+        },
+        _ => None,
+    };
+    Decoded::Complete(decoded.map(Event::Key), consumed)
+}
+
+fn decode_mouse(cb: u16, cx: u16, cy: u16, press: bool) -> Option<Mouse> {
+    // Motion events are not requested, but ignore them if the terminal sends them anyway.
+    if cb & 32 != 0 {
+        return None;
+    }
+    if cb & 64 != 0 {
+        return match cb & 3 {
+            0 => Some(Mouse::WheelUp(cx, cy)),
+            1 => Some(Mouse::WheelDown(cx, cy)),
+            _ => None,
+        };
+    }
+    let button = match cb & 3 {
+        0 => MouseButton::Left,
+        1 => MouseButton::Middle,
+        2 => MouseButton::Right,
+        _ => return Some(Mouse::Release(cx, cy)),
+    };
+    Some(if press {
+        Mouse::Press(button, cx, cy)
+    } else {
+        Mouse::Release(cx, cy)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(input: &[u8]) -> Vec<Event> {
+        Parser::default().feed(input)
+    }
+
+    #[test]
+    fn decodes_arrows() {
+        assert_eq!(
+            keys(b"\x1b[A\x1b[B\x1b[C\x1b[D\x1b[1;5A"),
+            [Key::Up, Key::Down, Key::Right, Key::Left, Key::Up].map(Event::Key)
+        );
+    }
+
+    #[test]
+    fn decodes_tilde_keys() {
+        assert_eq!(
+            keys(b"\x1b[1~\x1b[3~\x1b[4~\x1b[5~\x1b[6~\x1b[15~\x1b[24~\x1b[99~"),
+            [
+                Key::Home,
+                Key::Delete,
+                Key::End,
+                Key::PageUp,
+                Key::PageDown,
+                Key::F(5),
+                Key::F(12),
+            ]
+            .map(Event::Key)
+        );
+    }
+
+    #[test]
+    fn decodes_ss3_sequences() {
+        assert_eq!(
+            keys(b"\x1bOA\x1bOH\x1bOF\x1bOP\x1bOS"),
+            [Key::Up, Key::Home, Key::End, Key::F(1), Key::F(4)].map(Event::Key)
+        );
+    }
+
+    #[test]
+    fn decodes_characters() {
+        assert_eq!(
+            keys("aé日🎉\r\t\x7f\x12\x1bx\x1b".as_bytes()),
+            [
+                Key::Char('a'),
+                Key::Char('é'),
+                Key::Char('日'),
+                Key::Char('🎉'),
+                Key::Enter,
+                Key::Tab,
+                Key::Backspace,
+                Key::Ctrl('r'),
+                Key::Alt('x'),
+                Key::Esc,
+            ]
+            .map(Event::Key)
+        );
+    }
+
+    #[test]
+    fn decodes_sgr_mouse() {
+        assert_eq!(
+            keys(b"\x1b[<0;12;5M\x1b[<0;12;5m\x1b[<2;300;200M\x1b[<64;1;2M\x1b[<65;1;2M\x1b[<32;3;4M"),
+            [
+                Mouse::Press(MouseButton::Left, 12, 5),
+                Mouse::Release(12, 5),
+                Mouse::Press(MouseButton::Right, 300, 200),
+                Mouse::WheelUp(1, 2),
+                Mouse::WheelDown(1, 2),
+            ]
+            .map(Event::Mouse)
+        );
+    }
+
+    #[test]
+    fn keeps_sequences_split_across_reads() {
+        let mut parser = Parser::default();
+        assert_eq!(parser.feed(b"j\x1b["), [Event::Key(Key::Char('j'))]);
+        assert_eq!(parser.feed(b"5"), []);
+        assert_eq!(parser.feed(b"~\x1bO"), [Event::Key(Key::PageUp)]);
+        assert_eq!(parser.feed(b"B\x1b[<0;1"), [Event::Key(Key::Down)]);
+        assert_eq!(
+            parser.feed(b"0;20M\xe6\x97"),
+            [Event::Mouse(Mouse::Press(MouseButton::Left, 10, 20))]
+        );
+        assert_eq!(parser.feed(b"\xa5"), [Event::Key(Key::Char('日'))]);
+    }
+}
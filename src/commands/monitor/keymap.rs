@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+
+use strum::IntoEnumIterator;
+
+use super::input::Key;
+use crate::config::Action;
+
+fn default_keys(action: Action) -> Vec<Key> {
+    match action {
+        Action::Quit => vec![Key::Char('q')],
+        Action::Suspend => vec![Key::Ctrl('z')],
+        Action::Pause => vec![Key::Char('p')],
+        Action::Up => vec![Key::Up, Key::Char('k')],
+        Action::Down => vec![Key::Down, Key::Char('j')],
+        Action::PageUp => vec![Key::PageUp],
+        Action::PageDown => vec![Key::PageDown],
+        Action::First => vec![Key::Home, Key::Char('g')],
+        Action::Last => vec![Key::End],
+        Action::Open => vec![Key::Enter],
+        Action::Search => vec![Key::Char('/')],
+        Action::FilterVerdict => vec![Key::Char('v')],
+        Action::FilterLanguage => vec![Key::Char('l')],
+        Action::ClearFilter => vec![Key::Esc],
+        Action::Sort => vec![Key::Char('s')],
+        Action::SortOrder => vec![Key::Char('o')],
+        Action::Tasks => vec![Key::Tab],
+        Action::Diff => vec![Key::Char('d')],
+        Action::Export => vec![Key::Char('e')],
+        Action::Help => vec![Key::Char('?')],
+    }
+}
+
+/// The keys bound to each action.
+pub struct Keymap {
+    keys: Vec<(Action, Vec<Key>)>,
+    actions: HashMap<Key, Action>,
+}
+
+impl Keymap {
+    /// Builds the keymap from the defaults and the bindings in the config.
+    /// A key bound in the config is removed from the action it was bound to by default.
+    pub fn new(bindings: &HashMap<Action, Vec<String>>) -> anyhow::Result<Keymap> {
+        let mut keys = vec![];
+        for action in Action::iter() {
+            let action_keys = match bindings.get(&action) {
+                Some(names) => names
+                    .iter()
+                    .map(|name| name.parse::<Key>())
+                    .collect::<anyhow::Result<Vec<_>>>()
+                    .map_err(|err| anyhow::anyhow!("Invalid keymap for {}: {}", action, err))?,
+                None => default_keys(action),
+            };
+            keys.push((action, action_keys));
+        }
+
+        let mut actions = HashMap::new();
+        for (action, action_keys) in &keys {
+            if bindings.contains_key(action) {
+                continue;
+            }
+            for key in action_keys {
+                actions.insert(*key, *action);
+            }
+        }
+        for (action, action_keys) in &keys {
+            if !bindings.contains_key(action) {
+                continue;
+            }
+            for key in action_keys {
+                if let Some(previous) = actions.insert(*key, *action) {
+                    anyhow::ensure!(
+                        !bindings.contains_key(&previous),
+                        "Key {} is bound to both {} and {}",
+                        key,
+                        previous,
+                        action
+                    );
+                }
+            }
+        }
+        for (action, action_keys) in &mut keys {
+            action_keys.retain(|key| actions.get(key) == Some(action));
+        }
+
+        Ok(Keymap { keys, actions })
+    }
+
+    pub fn action(&self, key: Key) -> Option<Action> {
+        self.actions.get(&key).copied()
+    }
+
+    pub fn keys(&self, action: Action) -> &[Key] {
+        self.keys
+            .iter()
+            .find(|(other, _)| *other == action)
+            .map_or(&[], |(_, keys)| keys)
+    }
+
+    /// Returns the first key of the action, which is the one shown in the footer.
+    pub fn primary(&self, action: Action) -> Option<Key> {
+        self.keys(action).first().copied()
+    }
+
+    /// Returns the lines of the help, pairing the keys with the description of each action.
+    pub fn help(&self) -> Vec<(String, &'static str)> {
+        let mut lines = self
+            .keys
+            .iter()
+            .filter(|(_, keys)| !keys.is_empty())
+            .map(|(action, keys)| {
+                let keys = keys
+                    .iter()
+                    .map(|key| key.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                (keys, action.description())
+            })
+            .collect::<Vec<_>>();
+        lines.push(("A-Z".to_string(), "Filter by task"));
+        lines.push(("Ctrl-C".to_string(), "Quit"));
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_keys_leave_task_letters_free() {
+        for action in Action::iter() {
+            for key in default_keys(action) {
+                assert!(
+                    !matches!(key, Key::Char('A'..='Z')),
+                    "{} is bound to {} by default, which filters by task",
+                    action,
+                    key
+                );
+            }
+        }
+    }
+}
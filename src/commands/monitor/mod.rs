@@ -0,0 +1,1568 @@
+mod columns;
+mod events;
+pub mod export;
+mod input;
+mod keymap;
+mod notify;
+mod rating;
+mod record;
+mod render;
+mod server;
+pub mod source;
+mod summary;
+mod terminal;
+mod view;
+mod virtual_contest;
+
+use crate::{
+    config::{self, Action, Column},
+    context::Context,
+    info,
+    session::Session,
+};
+use input::{Event, Key, Mouse, MouseButton};
+use std::io::Write;
+use tokio::{
+    signal::unix::{signal, SignalKind},
+    sync::Mutex,
+};
+#[cfg(not(feature = "dummy-submissions"))]
+use {crate::session, std::str::FromStr};
+
+static SUBMISSIONS: std::sync::LazyLock<Mutex<indexmap::IndexMap<u64, Submission>>> =
+    std::sync::LazyLock::new(|| Mutex::new(indexmap::IndexMap::new()));
+static STOPPED: std::sync::OnceLock<()> = std::sync::OnceLock::new();
+static PAUSED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+struct Message {
+    time: std::time::SystemTime,
+    kind: MessageKind,
+    message: String,
+}
+
+enum MessageKind {
+    Error,
+    Info,
+    Warning,
+}
+
+#[derive(Default)]
+struct PollState {
+    polls: usize,
+    last_success: Option<chrono::DateTime<chrono::Local>>,
+}
+
+static POLL_STATE: std::sync::LazyLock<Mutex<PollState>> =
+    std::sync::LazyLock::new(|| Mutex::new(PollState::default()));
+
+static MESSAGE: std::sync::LazyLock<Mutex<Option<Message>>> =
+    std::sync::LazyLock::new(|| Mutex::new(None));
+
+static VIEW: std::sync::LazyLock<Mutex<view::View>> =
+    std::sync::LazyLock::new(|| Mutex::new(view::View::default()));
+
+static REDRAW: tokio::sync::Notify = tokio::sync::Notify::const_new();
+
+/// The number of rows the selection moves per scroll wheel step.
+const WHEEL_ROWS: isize = 3;
+const DOUBLE_CLICK_INTERVAL: std::time::Duration = std::time::Duration::from_millis(400);
+
+/// Asks `screen_loop` to draw a new frame.
+fn request_redraw() {
+    REDRAW.notify_one();
+}
+
+async fn message(kind: MessageKind, message: String) {
+    let time = std::time::SystemTime::now();
+    let log = Message {
+        time,
+        kind,
+        message,
+    };
+    let mut locked = MESSAGE.lock().await;
+    *locked = Some(log);
+    request_redraw();
+}
+
+pub struct Options {
+    /// Whether to show scores for a heuristic contest.
+    pub heuristic: bool,
+    pub virtual_start: Option<String>,
+    pub virtual_duration: Option<String>,
+    /// The address to serve the submissions on.
+    pub serve: Option<std::net::SocketAddr>,
+}
+
+pub async fn main(context: Context, options: Options) -> anyhow::Result<()> {
+    let config = config::load()?;
+    let session = Session::load()
+        .ok_or_else(|| anyhow::anyhow!("Failed to create agent, have you logged in?"))?;
+
+    let keymap = std::sync::Arc::new(keymap::Keymap::new(&config.monitor.keymap)?);
+
+    let contest_url = context.contest_url;
+    let mut contest = get_contest(&session.client, &contest_url).await?;
+    contest.heuristic = options.heuristic || is_heuristic_contest(&contest_url);
+    if let Some(virtual_start) = &options.virtual_start {
+        let start_time = virtual_contest::parse_start(virtual_start)?;
+        let duration = match &options.virtual_duration {
+            Some(duration) => virtual_contest::parse_duration(duration)?,
+            None => match (contest.start_time, contest.end_time) {
+                (Some(start_time), Some(end_time)) => end_time - start_time,
+                _ => anyhow::bail!(
+                    "Could not find the length of the contest, please specify --virtual-duration"
+                ),
+            },
+        };
+        contest.start_time = Some(start_time);
+        contest.end_time = Some(start_time + duration);
+        contest.is_virtual = true;
+    }
+
+    let submissions_url = submissions_url(&contest_url, context.task.as_deref());
+    match &context.task {
+        Some(task) => {
+            info!("Monitoring task {} in contest {}", task, contest_url);
+            contest.title = format!("{} / {}", contest.title, task);
+        }
+        None => info!("Monitoring contest {}", contest_url),
+    }
+    let _ = export::TARGET.set((
+        format!(
+            "{}-submissions.{}",
+            contest_id(&contest_url),
+            config.monitor.export_format.extension()
+        )
+        .into(),
+        config.monitor.export_format,
+    ));
+
+    let _ = source::SESSION.set(session.clone());
+
+    // The standings of a past contest say nothing about a virtual one,
+    // and heuristic contests have their own rating, which is not estimated.
+    let estimate_thread = (!contest.is_virtual && !contest.heuristic).then(|| {
+        let session = session.clone();
+        let contest_url = contest_url.clone();
+        tokio::spawn(async move { rating::estimate_loop(session, contest_url).await })
+    });
+
+    // Binding early reports an address in use before the screen is taken over.
+    let server_thread = match options.serve {
+        Some(address) => {
+            let allow_origin = config
+                .monitor
+                .serve_allow_origin
+                .as_deref()
+                .map(|origin| {
+                    axum::http::HeaderValue::from_str(origin)
+                        .map_err(|_| anyhow::anyhow!("Invalid serve_allow_origin: {:?}", origin))
+                })
+                .transpose()?;
+            let listener = tokio::net::TcpListener::bind(address)
+                .await
+                .map_err(|err| anyhow::anyhow!("Failed to listen on {}: {}", address, err))?;
+            info!("Serving submissions on http://{}", address);
+            Some(tokio::spawn(async move {
+                server::serve(listener, allow_origin).await
+            }))
+        }
+        None => None,
+    };
+
+    let webhook_thread = (!config.monitor.webhooks.is_empty()).then(|| {
+        let webhooks = config.monitor.webhooks.clone();
+        let (title, contest_url) = (contest.title.clone(), contest_url.clone());
+        tokio::spawn(async move { notify::webhook_loop(webhooks, title, contest_url).await })
+    });
+
+    let polling_thread = tokio::spawn(async move { poll(session, &submissions_url).await });
+
+    let screen_thread = {
+        let keymap = keymap.clone();
+        tokio::spawn(async move { screen_loop(contest, config.monitor, &keymap).await })
+    };
+
+    let signal_thread = tokio::spawn(async move { signal_loop().await });
+
+    let input_thread = tokio::spawn(async move { input_loop(&keymap).await });
+
+    let result = {
+        let mut threads = vec![polling_thread, screen_thread, input_thread, signal_thread];
+        threads.extend(estimate_thread);
+        threads.extend(webhook_thread);
+        threads.extend(server_thread);
+        let (finished, _, remaining) = futures::future::select_all(threads).await;
+
+        STOPPED.get_or_init(|| ());
+
+        futures::future::join_all(remaining).await;
+
+        finished?
+    };
+    terminal::leave();
+
+    if result.is_ok() {
+        info!("Goodbye!");
+    }
+
+    result
+}
+
+/// Generates submissions whose verdicts change with `counter`.
+#[cfg(feature = "dummy-submissions")]
+fn dummy_submissions(
+    first_time: chrono::DateTime<chrono::Utc>,
+    counter: usize,
+) -> indexmap::IndexMap<u64, Submission> {
+    let mut submissions: indexmap::IndexMap<u64, Submission> = indexmap::IndexMap::new();
+    for i in 0..100 {
+        let time = first_time + chrono::Duration::seconds(i as _);
+        let label = (b'A' + (i % 7) as u8) as char;
+        let problem = format!("{} - Problem {}", label, label);
+        let task = format!("dummy_{}", label.to_ascii_lowercase());
+        let language = "Rust".to_string();
+        let score = (i * i * 12345) as u64;
+        let code_size = "1024 Byte".to_string();
+        let status = match (i + counter) % 11 {
+            0 => SubmissionStatus::Accepted,
+            1 => SubmissionStatus::WaitingJudge,
+            2 => SubmissionStatus::Judging,
+            3 => SubmissionStatus::WaitingRejudge,
+            4 => SubmissionStatus::WrongAnswer,
+            5 => SubmissionStatus::TimeLimitExceeded,
+            6 => SubmissionStatus::MemoryLimitExceeded,
+            7 => SubmissionStatus::RuntimeError,
+            8 => SubmissionStatus::CompileError,
+            9 => SubmissionStatus::OutputLimitExceeded,
+            10 => SubmissionStatus::InternalError,
+            _ => unreachable!(),
+        };
+        let execution_time = if i % 2 == 0 {
+            Some("100 ms".to_string())
+        } else {
+            None
+        };
+        let memory = if i % 2 == 0 {
+            Some("3612 KiB".to_string())
+        } else {
+            None
+        };
+        let detail = format!("https://example.com/submissions/{}", i);
+        let submission = Submission {
+            time,
+            problem,
+            task,
+            language,
+            score,
+            code_size,
+            status,
+            execution_time,
+            memory,
+            detail,
+        };
+        submissions.insert(i as _, submission);
+    }
+    submissions
+}
+
+#[cfg(feature = "dummy-submissions")]
+async fn poll(_session: Session, _submissions_url: &str) -> anyhow::Result<()> {
+    let mut counter = 0;
+    let first_time = chrono::Utc::now();
+    while STOPPED.get().is_none() {
+        counter += 1;
+        let submissions = dummy_submissions(first_time, counter);
+        events::merge(submissions, counter == 1).await;
+        {
+            let mut poll_state = POLL_STATE.lock().await;
+            poll_state.polls += 1;
+            poll_state.last_success = Some(chrono::Local::now());
+        }
+        request_redraw();
+
+        sleep_unless_stopped(std::time::Duration::from_secs(10)).await;
+        while PAUSED.load(std::sync::atomic::Ordering::Relaxed) {
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            if STOPPED.get().is_some() {
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+#[cfg(not(feature = "dummy-submissions"))]
+async fn poll(session: Session, submissions_url: &str) -> anyhow::Result<()> {
+    let mut failures = 0;
+    while STOPPED.get().is_none() {
+        let first_poll = POLL_STATE.lock().await.polls == 0;
+        // A page only has the latest 20 submissions, so the older ones are loaded once at first.
+        let result = if first_poll {
+            export::fetch_all(&session, submissions_url).await
+        } else {
+            fetch_submissions(&session, submissions_url).await
+        };
+        match result {
+            Ok(submissions) => {
+                failures = 0;
+                events::merge(submissions, first_poll).await;
+                {
+                    let mut poll_state = POLL_STATE.lock().await;
+                    poll_state.polls += 1;
+                    poll_state.last_success = Some(chrono::Local::now());
+                }
+                request_redraw();
+                sleep_unless_stopped(std::time::Duration::from_secs(5)).await;
+            }
+            Err(err) if is_transient(&err) => {
+                failures += 1;
+                let delay: u64 = (1 << failures.min(6)).min(60);
+                let last_success = match POLL_STATE.lock().await.last_success {
+                    Some(last_success) => last_success.format("%H:%M:%S").to_string(),
+                    None => "never".to_string(),
+                };
+                // Messages are shown for a second, so the countdown is sent every second.
+                for remaining in (1..=delay).rev() {
+                    if STOPPED.get().is_some() {
+                        break;
+                    }
+                    message(
+                        MessageKind::Warning,
+                        format!(
+                            "Connection lost, retrying in {}s (last update: {}) | {}",
+                            remaining, last_success, err
+                        ),
+                    )
+                    .await;
+                    sleep_unless_stopped(std::time::Duration::from_secs(1)).await;
+                }
+                continue;
+            }
+            Err(err) => return Err(err),
+        }
+        while PAUSED.load(std::sync::atomic::Ordering::Relaxed) {
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            if STOPPED.get().is_some() {
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(feature = "dummy-submissions"))]
+async fn fetch_submissions(
+    session: &Session,
+    submissions_url: &str,
+) -> anyhow::Result<indexmap::IndexMap<u64, Submission>> {
+    let mut response = session
+        .client
+        .get(submissions_url)
+        .send()
+        .await?
+        .error_for_status()?;
+    if session::is_logged_out(&response) {
+        message(
+            MessageKind::Warning,
+            "Session has expired, logging in again".to_string(),
+        )
+        .await;
+        session.relogin().await?;
+        response = session
+            .client
+            .get(submissions_url)
+            .send()
+            .await?
+            .error_for_status()?;
+    }
+    let submissions_html = response.text().await?;
+
+    parse_submissions(&submissions_html).map_err(|err| UnexpectedPage(err.to_string()).into())
+}
+
+/// The submissions page could not be parsed, e.g. because AtCoder is under maintenance.
+#[cfg(not(feature = "dummy-submissions"))]
+#[derive(Debug)]
+struct UnexpectedPage(String);
+
+#[cfg(not(feature = "dummy-submissions"))]
+impl std::fmt::Display for UnexpectedPage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unexpected page: {}", self.0)
+    }
+}
+
+#[cfg(not(feature = "dummy-submissions"))]
+impl std::error::Error for UnexpectedPage {}
+
+/// Returns the URL of the list of own submissions, optionally only to the task.
+fn submissions_url(contest_url: &str, task: Option<&str>) -> String {
+    match task {
+        Some(task) => format!("{}/submissions/me?f.Task={}", contest_url, task),
+        None => format!("{}/submissions/me", contest_url),
+    }
+}
+
+/// Returns the contest id at the end of the contest URL, e.g. `abc300`.
+pub fn contest_id(contest_url: &str) -> &str {
+    contest_url
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or_default()
+}
+
+/// Returns true if the error is likely to go away by retrying later.
+#[cfg(not(feature = "dummy-submissions"))]
+fn is_transient(err: &anyhow::Error) -> bool {
+    if err.is::<UnexpectedPage>() {
+        return true;
+    }
+    let Some(err) = err.downcast_ref::<reqwest::Error>() else {
+        return false;
+    };
+    match err.status() {
+        Some(status) => {
+            status.is_server_error()
+                || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                || status == reqwest::StatusCode::REQUEST_TIMEOUT
+        }
+        None => err.is_timeout() || err.is_connect() || err.is_request() || err.is_body(),
+    }
+}
+
+#[cfg(not(feature = "dummy-submissions"))]
+fn parse_submissions(
+    submissions_html: &str,
+) -> anyhow::Result<indexmap::IndexMap<u64, Submission>> {
+    let mut submissions = indexmap::IndexMap::new();
+
+    let html = scraper::Html::parse_document(submissions_html);
+    let rows_selector = scraper::Selector::parse("tbody tr").unwrap();
+    let rows = html.select(&rows_selector).collect::<Vec<_>>();
+    let rows = rows.iter().rev();
+    let td_selector = scraper::Selector::parse("td").unwrap();
+    for row in rows {
+        let mut cells = row.select(&td_selector);
+        let mut next_cell = || {
+            cells
+                .next()
+                .ok_or_else(|| anyhow::anyhow!("missing cell in submission row"))
+        };
+
+        let time = next_cell()?;
+        let problem = next_cell()?;
+        let _user = next_cell()?;
+        let lang = next_cell()?;
+        let score = next_cell()?;
+        let code_size = next_cell()?;
+
+        let status_elem = next_cell()?;
+        let status_text = status_elem.text().collect::<String>();
+        let status_text = status_text.trim().split(' ').next_back().unwrap();
+        let status = if status_text.contains('/') {
+            SubmissionStatus::Judging
+        } else {
+            SubmissionStatus::from_str(status_text)?
+        };
+        let (execution_time, memory) = if status_elem.attr("colspan") == Some("3") {
+            (None, None)
+        } else {
+            let execution_time = next_cell()?;
+            let memory = next_cell()?;
+            (
+                Some(execution_time.text().collect::<String>()),
+                Some(memory.text().collect::<String>()),
+            )
+        };
+        let detail = next_cell()?;
+        let detail = detail
+            .child_elements()
+            .next()
+            .and_then(|link| link.value().attr("href"))
+            .ok_or_else(|| anyhow::anyhow!("missing detail link"))?;
+        let detail = format!("https://atcoder.jp{}", detail);
+        let task = problem
+            .child_elements()
+            .next()
+            .and_then(|link| link.value().attr("href"))
+            .and_then(|href| href.rsplit('/').next())
+            .ok_or_else(|| anyhow::anyhow!("missing task link"))?
+            .to_string();
+
+        let id: u64 = score
+            .value()
+            .attr("data-id")
+            .ok_or_else(|| anyhow::anyhow!("missing submission id"))?
+            .parse()?;
+        let time = chrono::DateTime::parse_from_str(
+            &time.text().collect::<String>(),
+            "%Y-%m-%d %H:%M:%S%z",
+        )?
+        .with_timezone(&chrono::Utc);
+
+        let submission = Submission {
+            time,
+            problem: problem.text().collect(),
+            task,
+            language: lang.text().collect(),
+            score: score.text().collect::<String>().replace(',', "").parse()?,
+            code_size: code_size.text().collect(),
+            status,
+            execution_time,
+            memory,
+            detail,
+        };
+
+        submissions.insert(id, submission);
+    }
+
+    Ok(submissions)
+}
+
+async fn sleep_unless_stopped(duration: std::time::Duration) {
+    let until = std::time::Instant::now() + duration;
+    while std::time::Instant::now() < until {
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        if STOPPED.get().is_some() {
+            break;
+        }
+    }
+}
+
+async fn input_loop(keymap: &keymap::Keymap) -> anyhow::Result<()> {
+    let mut parser = input::Parser::default();
+    while STOPPED.get().is_none() {
+        let Some(input) = terminal::read_input(std::time::Duration::from_millis(100)).await? else {
+            continue;
+        };
+        for event in parser.feed(&input) {
+            if handle_event(event, keymap).await {
+                return Ok(());
+            }
+        }
+        request_redraw();
+    }
+
+    Ok(())
+}
+
+/// Handles an input event, returning true if the monitor should quit.
+async fn handle_event(event: Event, keymap: &keymap::Keymap) -> bool {
+    let key = match event {
+        Event::Key(key) => key,
+        Event::Mouse(mouse) => match handle_mouse(mouse, keymap).await {
+            Some(key) => key,
+            None => return false,
+        },
+    };
+    // Ctrl-C always quits, so that the monitor can be left whatever the keymap is.
+    if key == Key::Ctrl('c') {
+        return true;
+    }
+
+    let submissions = {
+        let locked = SUBMISSIONS.lock().await;
+        locked.clone()
+    };
+    let mut view = VIEW.lock().await;
+
+    if view.help {
+        view.help = false;
+        return false;
+    }
+
+    if view.diff.is_some() {
+        let page = view.height.max(1);
+        let diff = view.diff.as_mut().unwrap();
+        match keymap.action(key) {
+            Some(Action::Up) => diff.scroll = diff.scroll.saturating_sub(1),
+            Some(Action::Down) => diff.scroll += 1,
+            Some(Action::PageUp) => diff.scroll = diff.scroll.saturating_sub(page),
+            Some(Action::PageDown) => diff.scroll += page,
+            Some(Action::First) => diff.scroll = 0,
+            // `draw_diff` scrolls back to the last page.
+            Some(Action::Last) => diff.scroll = usize::MAX,
+            _ => view.diff = None,
+        }
+        return false;
+    }
+
+    if view.searching {
+        match key {
+            Key::Enter => view.searching = false,
+            Key::Esc => {
+                view.searching = false;
+                view.filter.text.clear();
+            }
+            Key::Backspace => {
+                view.filter.text.pop();
+            }
+            Key::Char(c) if !c.is_control() => view.filter.text.push(c),
+            key if keymap.action(key) == Some(Action::Suspend) => terminal::request_suspend(),
+            _ => {}
+        }
+        return false;
+    }
+
+    let ids = view.visible_ids(&submissions);
+    let page = view.height.max(1) as isize;
+    let Some(action) = keymap.action(key) else {
+        if let Key::Char(letter @ 'A'..='Z') = key {
+            let mut labels = submissions
+                .values()
+                .map(|submission| submission.label().to_string())
+                .collect::<Vec<_>>();
+            labels.sort();
+            labels.dedup();
+            view.filter.cycle_task(letter, &labels);
+        }
+        return false;
+    };
+    match action {
+        Action::Quit => return true,
+        Action::Suspend => terminal::request_suspend(),
+        Action::Pause => {
+            PAUSED.store(
+                !PAUSED.load(std::sync::atomic::Ordering::Relaxed),
+                std::sync::atomic::Ordering::Relaxed,
+            );
+        }
+        Action::Up => view.move_selection(&ids, -1),
+        Action::Down => view.move_selection(&ids, 1),
+        Action::PageUp => view.move_selection(&ids, -page),
+        Action::PageDown => view.move_selection(&ids, page),
+        Action::First => view.select_first(&ids),
+        Action::Last => view.select_last(),
+        Action::Open => {
+            let Some(index) = view.selected_index(&ids) else {
+                return false;
+            };
+            drop(view);
+            open_submission(ids[index]).await;
+        }
+        Action::Search => {
+            view.searching = true;
+            view.filter.text.clear();
+        }
+        Action::FilterVerdict => view.filter.cycle_verdict(),
+        Action::FilterLanguage => {
+            let mut languages = submissions
+                .values()
+                .map(|submission| submission.language.clone())
+                .collect::<Vec<_>>();
+            languages.sort();
+            languages.dedup();
+            view.filter.cycle_language(&languages);
+        }
+        Action::ClearFilter => view.filter = view::Filter::default(),
+        Action::Sort => view.sort.cycle_key(),
+        Action::SortOrder => view.sort.toggle_order(),
+        Action::Tasks => view.tasks = !view.tasks,
+        Action::Diff => {
+            let Some(index) = view.selected_index(&ids) else {
+                return false;
+            };
+            let id = ids[index];
+            let submission = &submissions[&id];
+            let previous = submissions
+                .iter()
+                .filter(|(other_id, other)| **other_id < id && other.task == submission.task)
+                .map(|(other_id, _)| *other_id)
+                .max();
+            let Some(previous) = previous else {
+                drop(view);
+                message(
+                    MessageKind::Warning,
+                    format!("No earlier submission to {}", submission.problem),
+                )
+                .await;
+                return false;
+            };
+            view.diff = Some(view::Diff {
+                ids: (previous, id),
+                lines: None,
+                scroll: 0,
+            });
+            let urls = (
+                submissions[&previous].detail.clone(),
+                submission.detail.clone(),
+            );
+            tokio::spawn(load_diff((previous, id), urls));
+        }
+        Action::Export => {
+            drop(view);
+            export::export_current().await;
+        }
+        Action::Help => view.help = true,
+    }
+
+    false
+}
+
+/// Fetches the sources of two submissions and shows their diff, unless it was closed meanwhile.
+async fn load_diff((old_id, new_id): (u64, u64), (old_url, new_url): (String, String)) {
+    let Some(session) = source::SESSION.get() else {
+        return;
+    };
+    let result = tokio::try_join!(
+        source::fetch(session, &old_url),
+        source::fetch(session, &new_url)
+    );
+    let mut view = VIEW.lock().await;
+    let Some(diff) = view
+        .diff
+        .as_mut()
+        .filter(|diff| diff.ids == (old_id, new_id))
+    else {
+        return;
+    };
+    match result {
+        Ok((old, new)) => {
+            diff.lines = Some(source::unified_diff(
+                &old_id.to_string(),
+                &old,
+                &new_id.to_string(),
+                &new,
+            ));
+        }
+        Err(err) => {
+            view.diff = None;
+            drop(view);
+            message(
+                MessageKind::Error,
+                format!("Failed to fetch the sources: {}", err),
+            )
+            .await;
+        }
+    }
+    request_redraw();
+}
+
+/// Handles a mouse event, returning the key to handle if it stands for one,
+/// e.g. a click on a footer hint or a double-click on a row.
+async fn handle_mouse(mouse: Mouse, keymap: &keymap::Keymap) -> Option<Key> {
+    let submissions = {
+        let locked = SUBMISSIONS.lock().await;
+        locked.clone()
+    };
+    let mut view = VIEW.lock().await;
+    if view.help {
+        if matches!(mouse, Mouse::Press(..)) {
+            view.help = false;
+        }
+        return None;
+    }
+    if let Some(diff) = &mut view.diff {
+        match mouse {
+            Mouse::WheelUp(..) => diff.scroll = diff.scroll.saturating_sub(WHEEL_ROWS as usize),
+            Mouse::WheelDown(..) => diff.scroll += WHEEL_ROWS as usize,
+            Mouse::Press(..) => view.diff = None,
+            _ => {}
+        }
+        return None;
+    }
+    let ids = view.visible_ids(&submissions);
+    match mouse {
+        Mouse::WheelUp(..) => view.move_selection(&ids, -WHEEL_ROWS),
+        Mouse::WheelDown(..) => view.move_selection(&ids, WHEEL_ROWS),
+        Mouse::Press(MouseButton::Left, x, y) => {
+            let (x, y) = (
+                (x as usize).saturating_sub(1),
+                (y as usize).saturating_sub(1),
+            );
+            if y == view.footer_row {
+                return view.footer_hint_at(x);
+            }
+            let index = view.index_at_row(y)?;
+            view.select_index(&ids, index);
+
+            let now = std::time::Instant::now();
+            let is_double_click = matches!(
+                view.last_click,
+                Some((last_index, last_time))
+                    if last_index == index && now.duration_since(last_time) < DOUBLE_CLICK_INTERVAL
+            );
+            if is_double_click && !view.searching {
+                view.last_click = None;
+                return keymap.primary(Action::Open);
+            }
+            view.last_click = Some((index, now));
+        }
+        _ => {}
+    }
+    None
+}
+
+async fn open_submission(id: u64) {
+    let url = {
+        let locked = SUBMISSIONS.lock().await;
+        let Some(submission) = locked.get(&id) else {
+            return;
+        };
+        submission.detail.clone()
+    };
+    if let Err(err) = open::that_detached(&url) {
+        message(MessageKind::Error, format!("Failed to open URL: {}", err)).await;
+    } else {
+        message(
+            MessageKind::Info,
+            format!("Opening submission detail: {}", url),
+        )
+        .await;
+    }
+}
+
+/// Stops the monitor on SIGINT, SIGTERM and SIGHUP, and suspends it on SIGTSTP.
+async fn signal_loop() -> anyhow::Result<()> {
+    let mut interrupt = signal(SignalKind::interrupt())?;
+    let mut terminate = signal(SignalKind::terminate())?;
+    let mut hangup = signal(SignalKind::hangup())?;
+    let mut suspend = signal(SignalKind::from_raw(libc::SIGTSTP))?;
+    let mut resume = signal(SignalKind::from_raw(libc::SIGCONT))?;
+    while STOPPED.get().is_none() {
+        tokio::select! {
+            _ = interrupt.recv() => break,
+            _ = terminate.recv() => break,
+            _ = hangup.recv() => break,
+            _ = suspend.recv() => terminal::suspend(),
+            _ = resume.recv() => {
+                terminal::resume()?;
+                request_redraw();
+            }
+            _ = tokio::time::sleep(std::time::Duration::from_millis(100)) => {}
+        }
+    }
+
+    Ok(())
+}
+
+async fn screen_loop(
+    contest: Contest,
+    monitor_config: config::MonitorConfig,
+    keymap: &keymap::Keymap,
+) -> anyhow::Result<()> {
+    let _guard = terminal::enter()?;
+    let mut screen = std::io::stdout();
+    let mut renderer = render::Renderer::default();
+    let mut generation = terminal::generation();
+    let mut window_change = signal(SignalKind::window_change())?;
+    let mut last_size = 0;
+    let mut exit_warned = false;
+    let mut last_update = std::time::Instant::now();
+    let mut prev_status = std::collections::HashMap::new();
+    let mut update_time = std::collections::HashMap::new();
+    while STOPPED.get().is_none() {
+        if !terminal::is_active() {
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            continue;
+        }
+        let (terminal_width, terminal_height) = termion::terminal_size()?;
+        let mut frame = render::Frame::new(terminal_width, terminal_height);
+        let line_width = (terminal_width as usize).saturating_sub(1);
+
+        let mut title = format!(
+            "{}{}{}",
+            termion::style::Bold,
+            contest.title,
+            termion::style::Reset
+        );
+        if let (true, Some(start_time), Some(end_time)) =
+            (contest.is_virtual, contest.start_time, contest.end_time)
+        {
+            title.push_str(&format!(
+                " | {}",
+                virtual_contest::render_timer(start_time, end_time)
+            ));
+        }
+        if let Some(estimate) = &*rating::ESTIMATE.lock().await {
+            title.push_str(&format!(" | {}", estimate.render()));
+        }
+
+        frame.put_line(
+            0,
+            &crate::log::strip_ansi_codes(&console::truncate_str(&title, line_width, "...")),
+        );
+
+        let error_message = {
+            let locked = MESSAGE.lock().await;
+            match &*locked {
+                Some(log) => {
+                    if log.time.elapsed().unwrap() < std::time::Duration::from_secs(1) {
+                        Some(format!(
+                            "{}{}: {}{}",
+                            match log.kind {
+                                MessageKind::Error =>
+                                    termion::color::Fg(termion::color::Red).to_string(),
+                                MessageKind::Info =>
+                                    termion::color::Fg(termion::color::Blue).to_string(),
+                                MessageKind::Warning =>
+                                    termion::color::Fg(termion::color::Yellow).to_string(),
+                            },
+                            match log.kind {
+                                MessageKind::Error => "Error",
+                                MessageKind::Info => "Info",
+                                MessageKind::Warning => "Warning",
+                            },
+                            log.message,
+                            termion::color::Fg(termion::color::Reset)
+                        ))
+                    } else {
+                        None
+                    }
+                }
+                None => None,
+            }
+        };
+
+        let polls = POLL_STATE.lock().await.polls;
+
+        let (view_message, searching) = {
+            let view = VIEW.lock().await;
+            let message = if view.filter.is_empty() && !view.searching {
+                (!view.sort.is_default()).then(|| format!("Sort: {}", view.sort.describe()))
+            } else {
+                let submissions = SUBMISSIONS.lock().await;
+                let count = format!(
+                    "{}/{}",
+                    view.visible_ids(&submissions).len(),
+                    submissions.len()
+                );
+                Some(if view.searching {
+                    format!("/{}_ ({})", view.filter.text, count)
+                } else if view.sort.is_default() {
+                    format!("Filter: {} ({})", view.filter.describe(), count)
+                } else {
+                    format!(
+                        "Filter: {} ({}) | Sort: {}",
+                        view.filter.describe(),
+                        count,
+                        view.sort.describe()
+                    )
+                })
+            };
+            (message, view.searching)
+        };
+
+        let (footer_message, mut footer_hints) = if let Some(message) = error_message {
+            (message, vec![])
+        } else {
+            let (state, hints) = if searching {
+                ("  ".to_string(), search_hints())
+            } else if PAUSED.load(std::sync::atomic::Ordering::Relaxed) {
+                ("  Paused | ".to_string(), list_hints(keymap, true))
+            } else {
+                let spinner = match polls % 4 {
+                    0 => "|",
+                    1 => "/",
+                    2 => "-",
+                    3 => "\\",
+                    _ => unreachable!(),
+                };
+                (format!("{} Running | ", spinner), list_hints(keymap, false))
+            };
+            let prefix = format!(
+                "{}{}",
+                state,
+                view_message
+                    .map(|message| format!("{} | ", message))
+                    .unwrap_or_default()
+            );
+            render_hints(prefix, &hints)
+        };
+        if console::measure_text_width(&footer_message) > line_width {
+            // Hints hidden behind the ellipsis cannot be clicked.
+            footer_hints.retain(|(range, _)| range.end <= line_width.saturating_sub(3));
+        }
+        {
+            let mut view = VIEW.lock().await;
+            view.footer_row = terminal_height.saturating_sub(1) as usize;
+            view.footer_hints = footer_hints;
+        }
+        frame.put_line(
+            terminal_height.saturating_sub(1),
+            &crate::log::strip_ansi_codes(&format!(
+                "{}{}",
+                termion::color::Fg(termion::color::LightBlack),
+                console::truncate_str(&footer_message, line_width, "...")
+            )),
+        );
+        let submissions = {
+            let locked = SUBMISSIONS.lock().await;
+            locked.clone()
+        };
+        if last_size != submissions.len() {
+            last_size = submissions.len();
+            exit_warned = false;
+            last_update = std::time::Instant::now();
+        }
+
+        let trend = contest
+            .heuristic
+            .then(|| summary::ScoreTrend::new(&submissions));
+        let summary = summary::Summary::new(submissions.values());
+        let summary = match &trend {
+            Some(trend) => summary.render_heuristic(trend, line_width),
+            None => summary.render(),
+        };
+        frame.put_line(
+            1,
+            &crate::log::strip_ansi_codes(&columns::truncate(&summary, line_width)),
+        );
+
+        // The title, the summary and the footer take a row each.
+        let list_height = terminal_height.saturating_sub(3);
+        let (ids, range, selected_index, top) = {
+            let mut view = VIEW.lock().await;
+            let ids = view.visible_ids(&submissions);
+            let range = if view.tasks {
+                let lines = summary::render_tasks(
+                    &summary::TaskStats::collect(submissions.values()),
+                    contest.start_time,
+                    chrono::Duration::minutes(monitor_config.penalty_minutes.into()),
+                    line_width,
+                );
+                for (row, line) in lines.iter().take(list_height as usize).enumerate() {
+                    frame.put_line(
+                        2 + row as u16,
+                        &crate::log::strip_ansi_codes(&columns::truncate(line, line_width)),
+                    );
+                }
+                0..0
+            } else {
+                view.visible_range(&ids, list_height as usize)
+            };
+            let selected_index = view.selected_index(&ids);
+            // Align the list to the bottom so that the newest submission is next to the footer.
+            let top = 2 + list_height as usize - range.len();
+            view.list_top = top;
+            view.list_rows = range.len();
+            (ids, range, selected_index, top)
+        };
+        // One column is taken by the selection marker.
+        let mut list_columns = monitor_config.columns.clone();
+        if contest.is_virtual && !list_columns.contains(&Column::Elapsed) {
+            let position = list_columns
+                .iter()
+                .position(|&column| column == Column::Time)
+                .map_or(list_columns.len().min(1), |index| index + 1);
+            list_columns.insert(position, Column::Elapsed);
+        }
+        let layout = columns::layout(
+            &list_columns,
+            line_width.saturating_sub(1),
+            contest.heuristic,
+        );
+        let cell_context = columns::CellContext {
+            trend: trend.as_ref(),
+            start_time: contest.start_time,
+        };
+        for (row, index) in range.enumerate() {
+            let id = &ids[index];
+            let submission = &submissions[id];
+            let is_selected = selected_index == Some(index);
+
+            let status_color = status_color(submission.status);
+            match prev_status.get(id) {
+                Some(prev_status) if prev_status != &submission.status => {
+                    update_time.insert(*id, std::time::Instant::now());
+                }
+                _ => {}
+            }
+            prev_status.insert(*id, submission.status);
+
+            let is_updated_recently = match update_time.get(id) {
+                Some(update_time) => update_time.elapsed() < std::time::Duration::from_secs(5),
+                None => false,
+            };
+            let global_style = if matches!(
+                submission.status,
+                SubmissionStatus::WaitingJudge
+                    | SubmissionStatus::Judging
+                    | SubmissionStatus::WaitingRejudge
+            ) {
+                termion::color::Fg(termion::color::LightBlack).to_string()
+            } else if is_updated_recently {
+                termion::style::Bold.to_string()
+            } else {
+                "".to_string()
+            };
+
+            let mut sections = vec![
+                if is_selected { ">" } else { " " }.to_string(),
+                global_style.clone(),
+            ];
+            let mut previous_column = None;
+            for &(column, width) in &layout {
+                if let Some(previous_column) = previous_column {
+                    sections.push(columns::separator(previous_column).to_string());
+                }
+                previous_column = Some(column);
+
+                let cell = columns::render_cell(column, *id, submission, width, &cell_context);
+                if column == Column::Status {
+                    sections.push(format!(
+                        "{}{}{}{}",
+                        status_color,
+                        cell,
+                        termion::style::Reset,
+                        global_style
+                    ));
+                } else {
+                    sections.push(cell);
+                }
+            }
+
+            let mut line = columns::truncate(&sections.join(""), line_width);
+            if is_selected {
+                let padding = line_width.saturating_sub(console::measure_text_width(&line));
+                line = format!(
+                    "{}{}{}",
+                    termion::style::Invert,
+                    line.replace(
+                        &termion::style::Reset.to_string(),
+                        &format!("{}{}", termion::style::Reset, termion::style::Invert)
+                    ),
+                    " ".repeat(padding)
+                );
+            }
+            frame.put_line((top + row) as u16, &crate::log::strip_ansi_codes(&line));
+        }
+
+        {
+            let mut view = VIEW.lock().await;
+            if let Some(diff) = &mut view.diff {
+                draw_diff(&mut frame, diff);
+            }
+            if view.help {
+                draw_help(&mut frame, keymap);
+            }
+        }
+
+        if terminal::generation() != generation {
+            generation = terminal::generation();
+            renderer.invalidate();
+        }
+        renderer.render(frame, &mut screen)?;
+
+        // Time-dependent parts such as messages and countdowns still need an occasional redraw.
+        tokio::select! {
+            _ = REDRAW.notified() => {}
+            _ = window_change.recv() => {}
+            _ = tokio::time::sleep(std::time::Duration::from_secs(1)) => {}
+        }
+        if !PAUSED.load(std::sync::atomic::Ordering::Relaxed) {
+            if last_update.elapsed() > std::time::Duration::from_secs(60 * 59) && !exit_warned {
+                exit_warned = true;
+                message(
+                    MessageKind::Warning,
+                    "No new submissions for 59 minutes, will pause polling.".to_string(),
+                )
+                .await;
+            }
+            if last_update.elapsed() > std::time::Duration::from_secs(60 * 60) {
+                PAUSED.store(true, std::sync::atomic::Ordering::Relaxed);
+                last_update = std::time::Instant::now();
+            }
+        }
+    }
+    let (_terminal_width, terminal_height) = termion::terminal_size()?;
+    print!("{}", termion::cursor::Goto(1, terminal_height));
+    print!("{}", termion::clear::CurrentLine);
+    print!("Stopping...");
+
+    screen.flush()?;
+
+    Ok(())
+}
+
+/// Draws the diff in a box over the list, clamping its scroll position.
+fn draw_diff(frame: &mut render::Frame, diff: &mut view::Diff) {
+    let gray = termion::color::Fg(termion::color::LightBlack);
+    let reset = termion::style::Reset;
+    let lines = match &diff.lines {
+        None => vec![format!("{}Fetching the sources...{}", gray, reset)],
+        Some(lines) if lines.is_empty() => vec![format!("{}No changes{}", gray, reset)],
+        Some(lines) => lines.iter().map(|line| source::colorize(line)).collect(),
+    };
+
+    let (width, height) = (frame.width() as usize, frame.height() as usize);
+    let inner_width = width.saturating_sub(4);
+    let box_height = height.saturating_sub(2).max(4).min(height);
+    // The borders and the hint take three rows.
+    let rows = box_height.saturating_sub(3);
+    diff.scroll = diff.scroll.min(lines.len().saturating_sub(rows));
+    let x = (width.saturating_sub(inner_width + 4) / 2) as u16;
+    let y = (height.saturating_sub(box_height) / 2) as u16;
+
+    let title = format!(" Diff {} → {} ", diff.ids.0, diff.ids.1);
+    frame.put_text(
+        x,
+        y,
+        &format!(
+            "┌{}{}┐",
+            title,
+            "─".repeat((inner_width + 2).saturating_sub(console::measure_text_width(&title)))
+        ),
+    );
+    let hint = format!(
+        "{}Lines {}-{} of {}, scroll with ↑/↓ or the wheel, press any other key to close{}",
+        gray,
+        (diff.scroll + 1).min(lines.len()),
+        (diff.scroll + rows).min(lines.len()),
+        lines.len(),
+        reset
+    );
+    let visible = lines.iter().skip(diff.scroll).take(rows);
+    let rows = visible
+        .cloned()
+        .chain(std::iter::repeat(String::new()))
+        .take(rows)
+        .chain(std::iter::once(hint));
+    for (i, row) in rows.enumerate() {
+        let row = columns::truncate(&row, inner_width);
+        let padding = inner_width.saturating_sub(console::measure_text_width(&row));
+        frame.put_text(
+            x,
+            y + 1 + i as u16,
+            &format!("│ {}{}{} │", row, reset, " ".repeat(padding)),
+        );
+    }
+    frame.put_text(
+        x,
+        y + box_height.saturating_sub(1) as u16,
+        &format!("└{}┘", "─".repeat(inner_width + 2)),
+    );
+}
+
+/// Draws the keys of every action in a box at the center of the screen.
+fn draw_help(frame: &mut render::Frame, keymap: &keymap::Keymap) {
+    let lines = keymap.help();
+    let keys_width = lines
+        .iter()
+        .map(|(keys, _)| console::measure_text_width(keys))
+        .max()
+        .unwrap_or(0);
+    let mut rows = lines
+        .iter()
+        .map(|(keys, description)| {
+            format!(
+                "{}{}{}{}  {}",
+                termion::style::Bold,
+                keys,
+                termion::style::Reset,
+                " ".repeat(keys_width - console::measure_text_width(keys)),
+                description
+            )
+        })
+        .collect::<Vec<_>>();
+    rows.push(String::new());
+    rows.push(format!(
+        "{}Press any key to close{}",
+        termion::color::Fg(termion::color::LightBlack),
+        termion::style::Reset
+    ));
+
+    let (width, height) = (frame.width() as usize, frame.height() as usize);
+    let inner_width = rows
+        .iter()
+        .map(|row| console::measure_text_width(row))
+        .max()
+        .unwrap_or(0)
+        .min(width.saturating_sub(4));
+    let box_height = (rows.len() + 2).min(height);
+    let x = (width.saturating_sub(inner_width + 4) / 2) as u16;
+    let y = (height.saturating_sub(box_height) / 2) as u16;
+
+    let title = " Help ";
+    frame.put_text(
+        x,
+        y,
+        &format!(
+            "┌{}{}┐",
+            title,
+            "─".repeat((inner_width + 2).saturating_sub(title.len()))
+        ),
+    );
+    for (i, row) in rows.iter().take(box_height.saturating_sub(2)).enumerate() {
+        let row = columns::truncate(row, inner_width);
+        let padding = inner_width.saturating_sub(console::measure_text_width(&row));
+        frame.put_text(
+            x,
+            y + 1 + i as u16,
+            &format!("│ {}{} │", row, " ".repeat(padding)),
+        );
+    }
+    frame.put_text(
+        x,
+        y + box_height.saturating_sub(1) as u16,
+        &format!("└{}┘", "─".repeat(inner_width + 2)),
+    );
+}
+
+/// Keys shown together in the footer, e.g. `A-Z/v/l to filter`.
+/// Keys with a `Key` run it when clicked.
+struct Hint {
+    keys: Vec<(String, Option<Key>)>,
+    description: &'static str,
+}
+
+impl Hint {
+    /// Creates a hint showing the first key of each action, or `None` if none of them has a key.
+    fn new(keymap: &keymap::Keymap, actions: &[Action], description: &'static str) -> Option<Hint> {
+        let keys = actions
+            .iter()
+            .filter_map(|&action| keymap.primary(action))
+            .map(|key| (key.to_string(), Some(key)))
+            .collect::<Vec<_>>();
+        (!keys.is_empty()).then_some(Hint { keys, description })
+    }
+}
+
+fn search_hints() -> Vec<Hint> {
+    vec![
+        Hint {
+            keys: vec![("Enter".to_string(), Some(Key::Enter))],
+            description: "to apply",
+        },
+        Hint {
+            keys: vec![("Esc".to_string(), Some(Key::Esc))],
+            description: "to cancel",
+        },
+    ]
+}
+
+fn list_hints(keymap: &keymap::Keymap, paused: bool) -> Vec<Hint> {
+    let mut filter = Hint::new(
+        keymap,
+        &[Action::FilterVerdict, Action::FilterLanguage],
+        "to filter",
+    )
+    .unwrap_or(Hint {
+        keys: vec![],
+        description: "to filter",
+    });
+    filter.keys.insert(0, ("A-Z".to_string(), None));
+
+    [
+        Hint::new(
+            keymap,
+            &[Action::Pause],
+            if paused { "to resume" } else { "to pause" },
+        ),
+        Hint::new(keymap, &[Action::Quit], "to quit"),
+        Hint::new(keymap, &[Action::Up, Action::Down], "to select"),
+        Hint::new(keymap, &[Action::Open], "to open submission detail"),
+        Hint::new(keymap, &[Action::Search], "to search"),
+        Some(filter),
+        Hint::new(keymap, &[Action::Sort, Action::SortOrder], "to sort"),
+        Hint::new(keymap, &[Action::Tasks], "for tasks"),
+        Hint::new(keymap, &[Action::Help], "for help"),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// Renders the hints after `prefix`, returning the text and the columns of the clickable keys.
+fn render_hints(prefix: String, hints: &[Hint]) -> (String, Vec<(std::ops::Range<usize>, Key)>) {
+    let mut text = prefix;
+    let mut x = console::measure_text_width(&text);
+    let mut regions = vec![];
+    for (i, hint) in hints.iter().enumerate() {
+        if i > 0 {
+            text.push_str(", ");
+            x += 2;
+        }
+        for (j, (label, key)) in hint.keys.iter().enumerate() {
+            if j > 0 {
+                text.push('/');
+                x += 1;
+            }
+            let width = console::measure_text_width(label);
+            if let Some(key) = key {
+                regions.push((x..x + width, *key));
+            }
+            text.push_str(&format!(
+                "{}{}{}{}",
+                termion::style::Bold,
+                label,
+                termion::style::Reset,
+                termion::color::Fg(termion::color::LightBlack)
+            ));
+            x += width;
+        }
+        text.push_str(&format!(" {}", hint.description));
+        x += 1 + console::measure_text_width(hint.description);
+    }
+    (text, regions)
+}
+
+#[derive(Debug, Clone)]
+struct Submission {
+    time: chrono::DateTime<chrono::Utc>,
+    problem: String,
+    /// The screen name of the task, e.g. `abc300_a`.
+    task: String,
+    language: String,
+    score: u64,
+    code_size: String,
+    status: SubmissionStatus,
+    execution_time: Option<String>,
+    memory: Option<String>,
+    detail: String,
+}
+
+impl Submission {
+    /// Returns the task label, e.g. `A` in `A - N-choice question` or `Ex` in ABC.
+    fn label(&self) -> &str {
+        self.problem.split(" - ").next().unwrap_or_default()
+    }
+
+    /// Parses an execution time such as `123 ms`.
+    fn execution_time_ms(&self) -> Option<u64> {
+        parse_leading_number(self.execution_time.as_deref()?)
+    }
+
+    /// Parses a memory usage such as `3612 KiB`.
+    fn memory_kib(&self) -> Option<u64> {
+        parse_leading_number(self.memory.as_deref()?)
+    }
+}
+
+fn parse_leading_number(text: &str) -> Option<u64> {
+    text.split_whitespace().next()?.parse().ok()
+}
+
+/// The order of the variants is used when sorting by verdict.
+#[derive(Debug, Copy, Clone, strum::Display, strum::EnumString, PartialEq, Eq, PartialOrd, Ord)]
+enum SubmissionStatus {
+    #[strum(serialize = "WJ")]
+    WaitingJudge,
+    #[strum(serialize = "WR")]
+    WaitingRejudge,
+    #[strum(serialize = "...")]
+    Judging,
+    #[strum(serialize = "AC")]
+    Accepted,
+    #[strum(serialize = "WA")]
+    WrongAnswer,
+    #[strum(serialize = "TLE")]
+    TimeLimitExceeded,
+    #[strum(serialize = "MLE")]
+    MemoryLimitExceeded,
+    #[strum(serialize = "RE")]
+    RuntimeError,
+    #[strum(serialize = "CE")]
+    CompileError,
+    #[strum(serialize = "OLE")]
+    OutputLimitExceeded,
+    #[strum(serialize = "IE")]
+    InternalError,
+}
+
+fn status_color(status: SubmissionStatus) -> String {
+    match status {
+        SubmissionStatus::Accepted => termion::color::Fg(termion::color::Green).to_string(),
+        SubmissionStatus::WaitingJudge
+        | SubmissionStatus::Judging
+        | SubmissionStatus::WaitingRejudge => {
+            termion::color::Fg(termion::color::LightBlack).to_string()
+        }
+        SubmissionStatus::WrongAnswer
+        | SubmissionStatus::TimeLimitExceeded
+        | SubmissionStatus::MemoryLimitExceeded
+        | SubmissionStatus::RuntimeError
+        | SubmissionStatus::CompileError
+        | SubmissionStatus::OutputLimitExceeded => {
+            termion::color::Fg(termion::color::Yellow).to_string()
+        }
+        SubmissionStatus::InternalError => termion::color::Fg(termion::color::Red).to_string(),
+    }
+}
+
+impl SubmissionStatus {
+    fn is_pending(self) -> bool {
+        matches!(
+            self,
+            SubmissionStatus::WaitingJudge
+                | SubmissionStatus::Judging
+                | SubmissionStatus::WaitingRejudge
+        )
+    }
+
+    fn is_rejected(self) -> bool {
+        !self.is_pending() && self != SubmissionStatus::Accepted
+    }
+}
+
+/// The contest being monitored.
+struct Contest {
+    title: String,
+    start_time: Option<chrono::DateTime<chrono::Utc>>,
+    end_time: Option<chrono::DateTime<chrono::Utc>>,
+    /// Whether scores matter more than verdicts, as in AHC.
+    heuristic: bool,
+    /// Whether the start and end times are of a virtual contest.
+    is_virtual: bool,
+}
+
+fn is_heuristic_contest(contest_url: &str) -> bool {
+    contest_url
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .is_some_and(|contest_id| contest_id.starts_with("ahc"))
+}
+
+#[cfg(not(feature = "dummy-submissions"))]
+async fn get_contest(client: &reqwest::Client, contest_url: &str) -> anyhow::Result<Contest> {
+    let html = client
+        .get(contest_url)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    let title = {
+        let document = scraper::Html::parse_document(&html);
+        let title_selector = scraper::Selector::parse("title").unwrap();
+        let title = document
+            .select(&title_selector)
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Contest page has no title"))?;
+        let title = title.text().collect::<String>();
+        title.split(" - ").next().unwrap_or_default().to_string()
+    };
+    Ok(Contest {
+        title,
+        start_time: parse_moment(&html, "startTime"),
+        end_time: parse_moment(&html, "endTime"),
+        heuristic: false,
+        is_virtual: false,
+    })
+}
+
+/// Extracts a time such as `var startTime = moment("2024-01-01T21:00:00+09:00");` from a page.
+#[cfg(not(feature = "dummy-submissions"))]
+fn parse_moment(html: &str, name: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    let regex = regex::Regex::new(&format!(r#"var {} = moment\("([^"]*)"\);"#, name)).unwrap();
+    let captures = regex.captures(html)?;
+    chrono::DateTime::parse_from_rfc3339(&captures[1])
+        .ok()
+        .map(|time| time.to_utc())
+}
+
+#[cfg(feature = "dummy-submissions")]
+async fn get_contest(_client: &reqwest::Client, _contest_url: &str) -> anyhow::Result<Contest> {
+    let now = chrono::Utc::now();
+    Ok(Contest {
+        title: "Dummy Contest".to_string(),
+        start_time: Some(now - chrono::Duration::minutes(30)),
+        end_time: Some(now + chrono::Duration::minutes(70)),
+        heuristic: false,
+        is_virtual: false,
+    })
+}
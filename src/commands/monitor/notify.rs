@@ -0,0 +1,75 @@
+use std::sync::Arc;
+
+use tokio::sync::broadcast::error::RecvError;
+
+use super::{events, MessageKind, STOPPED};
+use crate::{
+    config::WebhookConfig,
+    webhook::{self, VerdictEvent},
+};
+
+fn verdict_event(change: &events::Change, contest: &str, contest_url: &str) -> VerdictEvent {
+    let submission = &change.submission;
+    VerdictEvent {
+        id: change.id,
+        contest: contest.to_string(),
+        contest_url: contest_url.to_string(),
+        problem: submission.problem.clone(),
+        status: submission.status.to_string(),
+        previous_status: change.previous.map(|status| status.to_string()),
+        score: submission.score,
+        language: submission.language.clone(),
+        time: submission.time,
+        execution_time_ms: submission.execution_time_ms(),
+        memory_kib: submission.memory_kib(),
+        url: submission.detail.clone(),
+    }
+}
+
+/// Posts every verdict change to the webhooks.
+pub async fn webhook_loop(
+    webhooks: Vec<WebhookConfig>,
+    contest: String,
+    contest_url: String,
+) -> anyhow::Result<()> {
+    // A separate client, so that the AtCoder cookies are never sent to webhooks.
+    let client = reqwest::Client::new();
+    let webhooks = Arc::new(webhooks);
+    let mut changes = events::subscribe();
+    while STOPPED.get().is_none() {
+        let change = tokio::select! {
+            change = changes.recv() => change,
+            _ = tokio::time::sleep(std::time::Duration::from_millis(100)) => continue,
+        };
+        let change = match change {
+            Ok(change) => change,
+            Err(RecvError::Lagged(skipped)) => {
+                super::message(
+                    MessageKind::Warning,
+                    format!("Skipped {} verdict changes for webhooks", skipped),
+                )
+                .await;
+                continue;
+            }
+            Err(RecvError::Closed) => break,
+        };
+
+        let event = Arc::new(verdict_event(&change, &contest, &contest_url));
+        for index in 0..webhooks.len() {
+            let (client, webhooks, event) = (client.clone(), webhooks.clone(), event.clone());
+            // Each request runs on its own, so that a slow webhook does not delay the others.
+            tokio::spawn(async move {
+                let config = &webhooks[index];
+                if let Err(err) = webhook::send(&client, config, &event).await {
+                    super::message(
+                        MessageKind::Warning,
+                        format!("Failed to send webhook to {}: {}", config.url, err),
+                    )
+                    .await;
+                }
+            });
+        }
+    }
+
+    Ok(())
+}
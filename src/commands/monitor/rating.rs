@@ -0,0 +1,400 @@
+//! Estimates the performance and the new rating during a contest, using the formulas in
+//! <https://img.atcoder.jp/file/AtCoder_rating_system_v2.pdf>.
+//!
+//! The formula for the performance uses the average performance (APerf) of each participant,
+//! which AtCoder does not publish. Their current rating stands in for it,
+//! so the estimate is only an approximation of the published result.
+
+use tokio::sync::Mutex;
+
+use super::{SubmissionStatus, STOPPED, SUBMISSIONS};
+use crate::session::Session;
+
+/// The estimate for the current standings.
+#[derive(Debug, Clone)]
+pub struct Estimate {
+    /// The rank in the standings, out of `participants`.
+    pub place: usize,
+    pub participants: usize,
+    pub performance: f64,
+    pub old_rating: Option<f64>,
+    pub new_rating: f64,
+}
+
+impl Estimate {
+    /// Renders the estimate for the header, e.g. `Rank 123/8000 | Perf 1534 | Rating 1200 → 1240 (+40)`.
+    pub fn render(&self) -> String {
+        let rating = match self.old_rating {
+            Some(old_rating) => format!(
+                "{} → {} ({:+})",
+                old_rating.round(),
+                self.new_rating.round(),
+                (self.new_rating.round() - old_rating.round())
+            ),
+            None => format!("{}", self.new_rating.round()),
+        };
+        format!(
+            "Rank {}/{} | Perf {} | Rating {}",
+            self.place,
+            self.participants,
+            self.performance.round(),
+            rating
+        )
+    }
+}
+
+pub static ESTIMATE: std::sync::LazyLock<Mutex<Option<Estimate>>> =
+    std::sync::LazyLock::new(|| Mutex::new(None));
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Standings {
+    standings_data: Vec<StandingsEntry>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct StandingsEntry {
+    rank: usize,
+    user_screen_name: String,
+    is_rated: bool,
+    rating: f64,
+    competitions: usize,
+    total_result: TotalResult,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TotalResult {
+    count: usize,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct HistoryEntry {
+    is_rated: bool,
+    new_rating: f64,
+    inner_performance: f64,
+    contest_screen_name: String,
+}
+
+/// Maps ratings below 400 to positive values, as shown on AtCoder.
+fn positivize(rating: f64) -> f64 {
+    if rating >= 400.0 {
+        rating
+    } else {
+        400.0 / ((400.0 - rating) / 400.0).exp()
+    }
+}
+
+/// Returns the performance at which the expected rank against `aperfs` equals `rank`.
+fn performance(aperfs: &[f64], rank: f64) -> f64 {
+    let expected_rank = |performance: f64| {
+        aperfs
+            .iter()
+            .map(|aperf| 1.0 / (1.0 + 6f64.powf((performance - aperf) / 400.0)))
+            .sum::<f64>()
+    };
+    // The expected rank decreases as the performance increases.
+    let (mut low, mut high) = (-10000.0, 10000.0);
+    for _ in 0..100 {
+        let middle = (low + high) / 2.0;
+        if expected_rank(middle) > rank - 0.5 {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    (low + high) / 2.0
+}
+
+/// Returns the rating after the contests with the given inner performances, newest first.
+fn rating(performances: &[f64]) -> f64 {
+    if performances.is_empty() {
+        return 0.0;
+    }
+    let (numerator, denominator) = performances.iter().enumerate().fold(
+        (0.0, 0.0),
+        |(numerator, denominator), (i, performance)| {
+            let weight = 0.9f64.powi(i as i32 + 1);
+            (
+                numerator + 2f64.powf(performance / 800.0) * weight,
+                denominator + weight,
+            )
+        },
+    );
+    let n = performances.len() as i32;
+    let correction = ((1.0 - 0.81f64.powi(n)).sqrt() / (1.0 - 0.9f64.powi(n)) - 1.0)
+        / (19f64.sqrt() - 1.0)
+        * 1200.0;
+    positivize(800.0 * (numerator / denominator).log2() - correction)
+}
+
+/// Returns the highest performance counted in the contest, which is 400 above the rated range.
+fn performance_cap(contest_id: &str) -> Option<f64> {
+    if contest_id.starts_with("abc") {
+        Some(2400.0)
+    } else if contest_id.starts_with("arc") {
+        Some(3200.0)
+    } else {
+        None
+    }
+}
+
+fn estimate(
+    contest_id: &str,
+    username: &str,
+    standings: &Standings,
+    history: &[HistoryEntry],
+) -> Option<Estimate> {
+    let participants = standings
+        .standings_data
+        .iter()
+        .filter(|entry| entry.total_result.count > 0)
+        .collect::<Vec<_>>();
+    let own = participants
+        .iter()
+        .find(|entry| entry.user_screen_name == username)?;
+    if !own.is_rated {
+        return None;
+    }
+
+    let rated = participants
+        .iter()
+        .filter(|entry| entry.is_rated)
+        .collect::<Vec<_>>();
+    // The performance is based on the rank among rated participants,
+    // and participants tied with us split the ranks they share.
+    let ahead = rated.iter().filter(|entry| entry.rank < own.rank).count();
+    let tied = rated.iter().filter(|entry| entry.rank == own.rank).count();
+    let rank = ahead as f64 + (1 + tied) as f64 / 2.0;
+
+    // The rating approximates the APerf of each participant, see the module documentation.
+    // The average performance of newcomers is assumed to be 1200, or 800 in ABC.
+    let newcomer_aperf = if contest_id.starts_with("abc") {
+        800.0
+    } else {
+        1200.0
+    };
+    let aperfs = rated
+        .iter()
+        .map(|entry| {
+            if entry.competitions == 0 {
+                newcomer_aperf
+            } else {
+                entry.rating
+            }
+        })
+        .collect::<Vec<_>>();
+    let mut inner_performance = performance(&aperfs, rank);
+    if let Some(cap) = performance_cap(contest_id) {
+        inner_performance = inner_performance.min(cap);
+    }
+
+    let past = history
+        .iter()
+        .filter(|entry| {
+            entry.is_rated
+                && !entry
+                    .contest_screen_name
+                    .starts_with(&format!("{}.", contest_id))
+        })
+        .collect::<Vec<_>>();
+    let performances = std::iter::once(inner_performance)
+        .chain(past.iter().rev().map(|entry| entry.inner_performance))
+        .collect::<Vec<_>>();
+
+    Some(Estimate {
+        place: own.rank,
+        participants: participants.len(),
+        performance: positivize(inner_performance),
+        old_rating: past.last().map(|entry| entry.new_rating),
+        new_rating: rating(&performances),
+    })
+}
+
+#[cfg(not(feature = "dummy-submissions"))]
+async fn fetch_standings(session: &Session, contest_url: &str) -> anyhow::Result<Standings> {
+    Ok(session
+        .client
+        .get(format!("{}/standings/json", contest_url))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?)
+}
+
+#[cfg(not(feature = "dummy-submissions"))]
+async fn fetch_history(session: &Session, username: &str) -> anyhow::Result<Vec<HistoryEntry>> {
+    Ok(session
+        .client
+        .get(format!(
+            "https://atcoder.jp/users/{}/history/json",
+            username
+        ))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?)
+}
+
+#[cfg(not(feature = "dummy-submissions"))]
+async fn fetch_username(session: &Session) -> anyhow::Result<String> {
+    session
+        .current_user()
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Not logged in"))
+}
+
+#[cfg(feature = "dummy-submissions")]
+async fn fetch_standings(_session: &Session, _contest_url: &str) -> anyhow::Result<Standings> {
+    let accepted = SUBMISSIONS
+        .lock()
+        .await
+        .values()
+        .filter(|submission| submission.status == SubmissionStatus::Accepted)
+        .count();
+    let standings_data = (0..8000)
+        .map(|i| StandingsEntry {
+            rank: i + 1,
+            user_screen_name: format!("user{}", i),
+            is_rated: i % 5 != 0,
+            rating: (3200 - i * 3200 / 8000) as f64,
+            competitions: i % 7,
+            total_result: TotalResult { count: 1 },
+        })
+        .chain(std::iter::once(StandingsEntry {
+            rank: 8000usize.saturating_sub(accepted * 500).max(1),
+            user_screen_name: "dummy".to_string(),
+            is_rated: true,
+            rating: 1200.0,
+            competitions: 10,
+            total_result: TotalResult { count: 1 },
+        }))
+        .collect();
+    Ok(Standings { standings_data })
+}
+
+#[cfg(feature = "dummy-submissions")]
+async fn fetch_history(_session: &Session, _username: &str) -> anyhow::Result<Vec<HistoryEntry>> {
+    Ok((0..10)
+        .map(|i| HistoryEntry {
+            is_rated: true,
+            new_rating: 1100.0 + i as f64 * 10.0,
+            inner_performance: 1200.0 + i as f64 * 20.0,
+            contest_screen_name: format!("abc{}.contest.atcoder.jp", 200 + i),
+        })
+        .collect())
+}
+
+#[cfg(feature = "dummy-submissions")]
+async fn fetch_username(_session: &Session) -> anyhow::Result<String> {
+    Ok("dummy".to_string())
+}
+
+/// Updates the estimate whenever the number of accepted submissions changes.
+pub async fn estimate_loop(session: Session, contest_url: String) -> anyhow::Result<()> {
+    let contest_id = super::contest_id(&contest_url).to_string();
+    let mut accepted = 0;
+    let mut history = None;
+    let mut username = None;
+    while STOPPED.get().is_none() {
+        let current = SUBMISSIONS
+            .lock()
+            .await
+            .values()
+            .filter(|submission| submission.status == SubmissionStatus::Accepted)
+            .count();
+        if current == accepted {
+            super::sleep_unless_stopped(std::time::Duration::from_secs(1)).await;
+            continue;
+        }
+        accepted = current;
+
+        // The standings take a moment to reflect the new verdict.
+        super::sleep_unless_stopped(std::time::Duration::from_secs(5)).await;
+        let result = async {
+            if username.is_none() {
+                username = Some(fetch_username(&session).await?);
+            }
+            let username = username.as_deref().unwrap_or_default();
+            if history.is_none() {
+                history = Some(fetch_history(&session, username).await?);
+            }
+            let standings = fetch_standings(&session, &contest_url).await?;
+            anyhow::Ok(estimate(
+                &contest_id,
+                username,
+                &standings,
+                history.as_deref().unwrap_or_default(),
+            ))
+        }
+        .await;
+        match result {
+            Ok(estimate) => {
+                *ESTIMATE.lock().await = estimate;
+                super::request_redraw();
+            }
+            Err(err) => {
+                super::message(
+                    super::MessageKind::Warning,
+                    format!("Failed to estimate the performance: {}", err),
+                )
+                .await
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 0.01,
+            "{} is not close to {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn positivize_maps_low_ratings_above_zero() {
+        assert_close(positivize(1600.0), 1600.0);
+        assert_close(positivize(400.0), 400.0);
+        assert_close(positivize(0.0), 400.0 / std::f64::consts::E);
+        assert_close(positivize(-400.0), 400.0 / std::f64::consts::E.powi(2));
+    }
+
+    #[test]
+    fn performance_matches_the_expected_rank() {
+        // Against one participant of the same strength, the first place is even.
+        assert_close(performance(&[1200.0], 1.0), 1200.0);
+        // Symmetric around 1200, so the middle place is 1200.
+        assert_close(performance(&[1000.0, 1200.0, 1400.0], 2.0), 1200.0);
+        // Winning against 10 participants of 1500: 10 / (1 + 6^((p - 1500) / 400)) = 0.5.
+        assert_close(
+            performance(&[1500.0; 10], 1.0),
+            1500.0 + 400.0 * 19f64.ln() / 6f64.ln(),
+        );
+    }
+
+    #[test]
+    fn rating_corrects_for_few_contests() {
+        assert_eq!(rating(&[]), 0.0);
+        // The correction for the first contest is 1200.
+        assert_close(rating(&[2000.0]), 800.0);
+        assert_close(rating(&[1000.0]), positivize(-200.0));
+        // The correction vanishes after many contests.
+        assert!((rating(&[1600.0; 100]) - 1600.0).abs() < 1.0);
+        // Weighted 0.9 and 0.81, with a correction of about 745.41 for two contests.
+        assert_close(rating(&[2000.0, 1000.0]), 884.19);
+        // Newer contests weigh more.
+        assert!(rating(&[2000.0, 1000.0]) > rating(&[1000.0, 2000.0]));
+    }
+}
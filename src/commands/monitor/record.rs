@@ -0,0 +1,39 @@
+use super::Submission;
+
+/// A submission as exposed outside the monitor, with numeric fields where possible.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Record {
+    pub id: u64,
+    pub time: chrono::DateTime<chrono::Utc>,
+    pub problem: String,
+    pub task: String,
+    pub language: String,
+    pub score: u64,
+    pub code_size_bytes: Option<u64>,
+    pub status: String,
+    /// Whether the submission is still being judged.
+    #[serde(skip)]
+    pub pending: bool,
+    pub execution_time_ms: Option<u64>,
+    pub memory_kib: Option<u64>,
+    pub url: String,
+}
+
+impl Record {
+    pub(super) fn new(id: u64, submission: &Submission) -> Self {
+        Self {
+            id,
+            time: submission.time,
+            problem: submission.problem.clone(),
+            task: submission.task.clone(),
+            language: submission.language.clone(),
+            score: submission.score,
+            code_size_bytes: super::parse_leading_number(&submission.code_size),
+            status: submission.status.to_string(),
+            pending: submission.status.is_pending(),
+            execution_time_ms: submission.execution_time_ms(),
+            memory_kib: submission.memory_kib(),
+            url: submission.detail.clone(),
+        }
+    }
+}
@@ -0,0 +1,259 @@
+use std::io::Write;
+
+use unicode_width::UnicodeWidthChar;
+
+/// The SGR attributes of a cell.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Style {
+    fg: Option<String>,
+    bg: Option<String>,
+    bold: bool,
+    dim: bool,
+    italic: bool,
+    underline: bool,
+    reverse: bool,
+}
+
+impl Style {
+    /// Applies the parameters of an SGR sequence (`\x1b[...m`).
+    fn apply(&mut self, params: &str) {
+        let params = params.split(';').collect::<Vec<_>>();
+        let mut i = 0;
+        while i < params.len() {
+            match params[i] {
+                "" | "0" => *self = Style::default(),
+                "1" => self.bold = true,
+                "2" => self.dim = true,
+                "3" => self.italic = true,
+                "4" => self.underline = true,
+                "7" => self.reverse = true,
+                "22" => {
+                    self.bold = false;
+                    self.dim = false;
+                }
+                "23" => self.italic = false,
+                "24" => self.underline = false,
+                "27" => self.reverse = false,
+                "39" => self.fg = None,
+                "49" => self.bg = None,
+                code @ ("38" | "48") => {
+                    let length = match params.get(i + 1) {
+                        Some(&"5") => 3,
+                        Some(&"2") => 5,
+                        _ => 1,
+                    };
+                    let end = (i + length).min(params.len());
+                    let color = params[i..end].join(";");
+                    if code == "38" {
+                        self.fg = Some(color);
+                    } else {
+                        self.bg = Some(color);
+                    }
+                    i = end;
+                    continue;
+                }
+                code => match code.parse::<u8>() {
+                    Ok(30..=37 | 90..=97) => self.fg = Some(code.to_string()),
+                    Ok(40..=47 | 100..=107) => self.bg = Some(code.to_string()),
+                    _ => {}
+                },
+            }
+            i += 1;
+        }
+    }
+
+    fn to_sgr(&self) -> String {
+        let mut params = vec!["0".to_string()];
+        if self.bold {
+            params.push("1".to_string());
+        }
+        if self.dim {
+            params.push("2".to_string());
+        }
+        if self.italic {
+            params.push("3".to_string());
+        }
+        if self.underline {
+            params.push("4".to_string());
+        }
+        if self.reverse {
+            params.push("7".to_string());
+        }
+        if let Some(fg) = &self.fg {
+            params.push(fg.clone());
+        }
+        if let Some(bg) = &self.bg {
+            params.push(bg.clone());
+        }
+        format!("\x1b[{}m", params.join(";"))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Cell {
+    /// The text drawn in this cell. Empty for the second half of a wide character.
+    symbol: String,
+    style: Style,
+}
+
+impl Cell {
+    fn blank() -> Cell {
+        Cell {
+            symbol: " ".to_string(),
+            style: Style::default(),
+        }
+    }
+}
+
+/// A screenful of cells to be drawn.
+pub struct Frame {
+    width: u16,
+    height: u16,
+    cells: Vec<Cell>,
+}
+
+impl Frame {
+    pub fn new(width: u16, height: u16) -> Frame {
+        Frame {
+            width,
+            height,
+            cells: vec![Cell::blank(); width as usize * height as usize],
+        }
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Draws a line of text with ANSI styles at row `y` (0-based), clipping it at the right edge.
+    pub fn put_line(&mut self, y: u16, line: &str) {
+        self.put_text(0, y, line);
+    }
+
+    /// Draws text with ANSI styles starting at column `x` of row `y` (0-based),
+    /// clipping it at the right edge.
+    pub fn put_text(&mut self, x: u16, y: u16, text: &str) {
+        if y >= self.height || x >= self.width {
+            return;
+        }
+        let row = y as usize * self.width as usize;
+        let width = self.width as usize;
+        let mut style = Style::default();
+        let mut x = x as usize;
+        // Do not leave half of a wide character that the text starts in the middle of.
+        if x > 0 && self.cells[row + x].symbol.is_empty() {
+            self.cells[row + x - 1] = Cell::blank();
+        }
+        let mut chars = text.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                if chars.peek() == Some(&'[') {
+                    chars.next();
+                    let mut params = String::new();
+                    for c in chars.by_ref() {
+                        if c.is_ascii_alphabetic() {
+                            if c == 'm' {
+                                style.apply(&params);
+                            }
+                            break;
+                        }
+                        params.push(c);
+                    }
+                }
+                continue;
+            }
+            let char_width = c.width().unwrap_or(0);
+            if char_width == 0 {
+                if x > 0 {
+                    let previous = if self.cells[row + x - 1].symbol.is_empty() && x > 1 {
+                        x - 2
+                    } else {
+                        x - 1
+                    };
+                    self.cells[row + previous].symbol.push(c);
+                }
+                continue;
+            }
+            if x + char_width > width {
+                break;
+            }
+            self.cells[row + x] = Cell {
+                symbol: c.to_string(),
+                style: style.clone(),
+            };
+            for offset in 1..char_width {
+                self.cells[row + x + offset] = Cell {
+                    symbol: String::new(),
+                    style: style.clone(),
+                };
+            }
+            x += char_width;
+        }
+        // Likewise for a wide character that the text ends in the middle of.
+        if x < width && self.cells[row + x].symbol.is_empty() {
+            self.cells[row + x] = Cell::blank();
+        }
+    }
+}
+
+/// Draws frames by only emitting the cells that changed since the previous frame.
+#[derive(Default)]
+pub struct Renderer {
+    previous: Option<Frame>,
+}
+
+impl Renderer {
+    /// Forgets the previous frame so that the next one is drawn from scratch.
+    pub fn invalidate(&mut self) {
+        self.previous = None;
+    }
+
+    pub fn render(&mut self, frame: Frame, out: &mut impl Write) -> std::io::Result<()> {
+        let previous = self
+            .previous
+            .take()
+            .filter(|previous| previous.width == frame.width && previous.height == frame.height);
+        if previous.is_none() {
+            write!(out, "{}{}", termion::style::Reset, termion::clear::All)?;
+        }
+
+        let mut cursor: Option<(usize, usize)> = None;
+        let mut current_style: Option<&Style> = None;
+        let width = frame.width as usize;
+        for (index, cell) in frame.cells.iter().enumerate() {
+            let (x, y) = (index % width, index / width);
+            let changed = match &previous {
+                Some(previous) => &previous.cells[index] != cell,
+                None => cell != &Cell::blank(),
+            };
+            if !changed || cell.symbol.is_empty() {
+                continue;
+            }
+
+            if cursor != Some((x, y)) {
+                write!(out, "{}", termion::cursor::Goto(x as u16 + 1, y as u16 + 1))?;
+            }
+            if current_style != Some(&cell.style) {
+                write!(out, "{}", cell.style.to_sgr())?;
+                current_style = Some(&cell.style);
+            }
+            write!(out, "{}", cell.symbol)?;
+            let symbol_width = cell
+                .symbol
+                .chars()
+                .next()
+                .and_then(|c| c.width())
+                .unwrap_or(1);
+            cursor = Some((x + symbol_width, y));
+        }
+        write!(out, "{}", termion::style::Reset)?;
+        out.flush()?;
+
+        self.previous = Some(frame);
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+//! Serves the submissions to browsers and other tools with `monitor --serve`.
+
+use axum::{
+    http::{header, HeaderValue},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Html, IntoResponse,
+    },
+    routing::get,
+    Json, Router,
+};
+use tokio_stream::{wrappers::BroadcastStream, StreamExt};
+
+use super::{events, record::Record, STOPPED, SUBMISSIONS};
+
+/// A page for stream overlays, showing the latest submissions.
+const OVERLAY: &str = include_str!("overlay.html");
+
+#[derive(serde::Serialize)]
+struct ChangeEvent {
+    previous_status: Option<String>,
+    submission: Record,
+}
+
+async fn submissions() -> impl IntoResponse {
+    let records = SUBMISSIONS
+        .lock()
+        .await
+        .iter()
+        .map(|(id, submission)| Record::new(*id, submission))
+        .collect::<Vec<_>>();
+    Json(records)
+}
+
+/// Streams verdict changes as `change` events.
+async fn changes() -> impl IntoResponse {
+    let stream = BroadcastStream::new(events::subscribe()).filter_map(|change| {
+        // A lagged client misses some changes, and can reload `/submissions` to catch up.
+        let change = change.ok()?;
+        Some(Event::default().event("change").json_data(ChangeEvent {
+            previous_status: change.previous.map(|status| status.to_string()),
+            submission: Record::new(change.id, &change.submission),
+        }))
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+async fn overlay() -> Html<&'static str> {
+    Html(OVERLAY)
+}
+
+/// Serves the submissions. Pages from other origins can only read them if `allow_origin` is set,
+/// as any website open in the browser could otherwise read them from localhost.
+pub async fn serve(
+    listener: tokio::net::TcpListener,
+    allow_origin: Option<HeaderValue>,
+) -> anyhow::Result<()> {
+    let mut app = Router::new()
+        .route("/submissions", get(submissions))
+        .route("/events", get(changes))
+        .route("/overlay", get(overlay));
+    if let Some(allow_origin) = allow_origin {
+        app = app.layer(axum::middleware::map_response(
+            move |mut response: axum::response::Response| {
+                let allow_origin = allow_origin.clone();
+                async move {
+                    response
+                        .headers_mut()
+                        .insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin);
+                    response
+                }
+            },
+        ));
+    }
+    // Open event streams never end by themselves, so the server is dropped instead of shut down.
+    tokio::select! {
+        result = axum::serve(listener, app) => result?,
+        _ = async {
+            while STOPPED.get().is_none() {
+                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            }
+        } => {}
+    }
+
+    Ok(())
+}
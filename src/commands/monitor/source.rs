@@ -0,0 +1,146 @@
+//! Source code of submissions, for `fetch-sources` and diffs.
+
+use crate::session::Session;
+
+/// The session used to fetch sources from the monitor, set when it starts.
+pub static SESSION: std::sync::OnceLock<Session> = std::sync::OnceLock::new();
+
+/// Returns the file extension for a language such as `C++ 20 (gcc 12.2)` or `Python3 (3.4.3)`.
+pub fn extension(language: &str) -> &'static str {
+    // The version follows the name, either in parentheses or right after it.
+    let name = language.split('(').next().unwrap_or_default();
+    let name = name
+        .trim_end_matches(|c: char| c.is_ascii_digit() || c == '.' || c == ' ')
+        .to_lowercase();
+    match known_extension(&name) {
+        Some(extension) => extension,
+        // e.g. `C# 11.0 AOT (.NET 7.0.7)`
+        None => name
+            .split(' ')
+            .next()
+            .and_then(known_extension)
+            .unwrap_or("txt"),
+    }
+}
+
+fn known_extension(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "c++" => "cpp",
+        "c" => "c",
+        "c#" => "cs",
+        "f#" => "fs",
+        "python" | "pypy" | "cython" => "py",
+        "rust" => "rs",
+        "java" => "java",
+        "kotlin" => "kt",
+        "scala" => "scala",
+        "go" => "go",
+        "ruby" => "rb",
+        "crystal" => "cr",
+        "javascript" => "js",
+        "typescript" => "ts",
+        "dart" => "dart",
+        "swift" => "swift",
+        "haskell" => "hs",
+        "ocaml" => "ml",
+        "nim" => "nim",
+        "zig" => "zig",
+        "d" => "d",
+        "julia" => "jl",
+        "perl" => "pl",
+        "raku" => "raku",
+        "php" => "php",
+        "bash" | "zsh" => "sh",
+        "lua" | "luajit" => "lua",
+        "elixir" => "ex",
+        "erlang" => "erl",
+        "common lisp" => "lisp",
+        "scheme" => "scm",
+        "clojure" => "clj",
+        "racket" => "rkt",
+        "fortran" => "f90",
+        "pascal" => "pas",
+        "r" => "r",
+        "awk" => "awk",
+        "sed" => "sed",
+        "vim" => "vim",
+        "cobol" => "cob",
+        "ada" => "adb",
+        "prolog" => "pro",
+        "v" => "v",
+        "text" => "txt",
+        _ => return None,
+    })
+}
+
+/// Returns the detail URL of a submission in the contest.
+pub fn detail_url(contest_url: &str, id: u64) -> String {
+    format!("{}/submissions/{}", contest_url, id)
+}
+
+/// Returns the lines of the unified diff from `old` to `new`.
+pub fn unified_diff(old_name: &str, old: &str, new_name: &str, new: &str) -> Vec<String> {
+    similar::TextDiff::from_lines(old, new)
+        .unified_diff()
+        .context_radius(3)
+        .header(old_name, new_name)
+        .to_string()
+        .lines()
+        // Tabs would break the alignment of the screen.
+        .map(|line| line.replace('\t', "    "))
+        .collect()
+}
+
+/// Colors a line of a unified diff like `git diff` does.
+pub fn colorize(line: &str) -> String {
+    let color = if line.starts_with("+++") || line.starts_with("---") {
+        termion::style::Bold.to_string()
+    } else if line.starts_with("@@") {
+        termion::color::Fg(termion::color::Cyan).to_string()
+    } else if line.starts_with('+') {
+        termion::color::Fg(termion::color::Green).to_string()
+    } else if line.starts_with('-') {
+        termion::color::Fg(termion::color::Red).to_string()
+    } else {
+        return line.to_string();
+    };
+    format!("{}{}{}", color, line, termion::style::Reset)
+}
+
+/// Fetches the source code on the detail page of a submission.
+#[cfg(not(feature = "dummy-submissions"))]
+pub async fn fetch(session: &Session, detail_url: &str) -> anyhow::Result<String> {
+    let mut response = session
+        .client
+        .get(detail_url)
+        .send()
+        .await?
+        .error_for_status()?;
+    // Used both inside and outside the monitor, so the relogin is not announced here.
+    if crate::session::is_logged_out(&response) {
+        session.relogin().await?;
+        response = session
+            .client
+            .get(detail_url)
+            .send()
+            .await?
+            .error_for_status()?;
+    }
+    let html = response.text().await?;
+    let html = scraper::Html::parse_document(&html);
+    let selector = scraper::Selector::parse("#submission-code").unwrap();
+    html.select(&selector)
+        .next()
+        .map(|code| code.text().collect())
+        .ok_or_else(|| anyhow::anyhow!("Submission page has no source code: {}", detail_url))
+}
+
+#[cfg(feature = "dummy-submissions")]
+pub async fn fetch(_session: &Session, detail_url: &str) -> anyhow::Result<String> {
+    let id = detail_url.rsplit('/').next().unwrap_or_default();
+    let id = id.parse::<u64>().unwrap_or_default();
+    Ok(format!(
+        "use std::io::Read;\n\nfn main() {{\n    let mut input = String::new();\n    std::io::stdin().read_to_string(&mut input).unwrap();\n    let n: u64 = input.trim().parse().unwrap();\n    println!(\"{{}}\", n * {});\n}}\n",
+        id % 3 + 1
+    ))
+}
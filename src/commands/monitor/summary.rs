@@ -0,0 +1,443 @@
+use std::collections::{BTreeMap, HashMap};
+
+use super::{Submission, SubmissionStatus};
+
+/// The overall progress in the contest.
+pub struct Summary {
+    counts: BTreeMap<SubmissionStatus, usize>,
+    total: usize,
+    pending: usize,
+    solved: usize,
+    /// The sum of the best score of each task.
+    score: u64,
+}
+
+impl Summary {
+    pub fn new<'a>(submissions: impl IntoIterator<Item = &'a Submission>) -> Summary {
+        let mut counts = BTreeMap::new();
+        let mut total = 0;
+        let mut best_scores = HashMap::new();
+        let mut solved_tasks = std::collections::HashSet::new();
+        for submission in submissions {
+            total += 1;
+            *counts.entry(submission.status).or_insert(0) += 1;
+            if submission.status.is_pending() {
+                continue;
+            }
+            let best = best_scores.entry(&submission.problem).or_insert(0);
+            *best = submission.score.max(*best);
+            if submission.status == SubmissionStatus::Accepted {
+                solved_tasks.insert(&submission.problem);
+            }
+        }
+        let pending = counts
+            .iter()
+            .filter(|(status, _)| status.is_pending())
+            .map(|(_, count)| count)
+            .sum();
+
+        Summary {
+            counts,
+            total,
+            pending,
+            solved: solved_tasks.len(),
+            score: best_scores.values().sum(),
+        }
+    }
+
+    /// Renders the summary as a line, e.g. `12 submissions (1 pending) | AC 5  WA 3 | 4 solved | 1200pts`.
+    pub fn render(&self) -> String {
+        let mut sections = vec![self.render_total()];
+        let counts = self
+            .counts
+            .iter()
+            .filter(|(status, _)| !status.is_pending())
+            .map(|(status, count)| {
+                format!(
+                    "{}{}{} {}",
+                    super::status_color(*status),
+                    status,
+                    termion::color::Fg(termion::color::Reset),
+                    count
+                )
+            })
+            .collect::<Vec<_>>();
+
+        if !counts.is_empty() {
+            sections.push(counts.join("  "));
+        }
+        sections.push(format!("{} solved", self.solved));
+        sections.push(format!("{}pts", format_score(self.score)));
+        format!(" {}", sections.join(" | "))
+    }
+
+    /// Renders the summary for heuristic contests, where the score trend replaces the verdicts.
+    pub fn render_heuristic(&self, trend: &ScoreTrend, width: usize) -> String {
+        let total = format!(" {} | ", self.render_total());
+        let trend = trend.render(width.saturating_sub(console::measure_text_width(&total)));
+        format!("{}{}", total, trend)
+    }
+
+    /// Renders the number of submissions, e.g. `12 submissions (1 pending)`.
+    fn render_total(&self) -> String {
+        let mut total = format!(
+            "{} submission{}",
+            self.total,
+            if self.total == 1 { "" } else { "s" }
+        );
+        if self.pending > 0 {
+            total.push_str(&format!(
+                " ({}{} pending{})",
+                termion::color::Fg(termion::color::LightBlack),
+                self.pending,
+                termion::color::Fg(termion::color::Reset)
+            ));
+        }
+        total
+    }
+}
+
+/// How the score improved over time, for heuristic contests.
+pub struct ScoreTrend {
+    /// The judged scores in the order of submission.
+    scores: Vec<u64>,
+    best: Option<u64>,
+    /// The best score before the current best was reached.
+    previous_best: Option<u64>,
+    /// The improvement over the previous best of each submission that set a new best.
+    improvements: HashMap<u64, Option<f64>>,
+}
+
+impl ScoreTrend {
+    pub fn new<'a>(submissions: impl IntoIterator<Item = (&'a u64, &'a Submission)>) -> ScoreTrend {
+        let mut judged = submissions
+            .into_iter()
+            .filter(|(_, submission)| !submission.status.is_pending())
+            .collect::<Vec<_>>();
+        judged.sort_by_key(|(_, submission)| submission.time);
+
+        let mut best = None;
+        let mut previous_best = None;
+        let mut improvements = HashMap::new();
+        for (id, submission) in &judged {
+            if best.is_some_and(|best| submission.score <= best) {
+                continue;
+            }
+            improvements.insert(
+                **id,
+                best.and_then(|best| improvement(best, submission.score)),
+            );
+            previous_best = best;
+            best = Some(submission.score);
+        }
+
+        ScoreTrend {
+            scores: judged
+                .iter()
+                .map(|(_, submission)| submission.score)
+                .collect(),
+            best,
+            previous_best,
+            improvements,
+        }
+    }
+
+    /// Returns whether the submission set a new best, and how much it improved it by.
+    pub fn improvement_of(&self, id: u64) -> Option<Option<f64>> {
+        self.improvements.get(&id).copied()
+    }
+
+    /// Renders the best score and the trend of the latest `width` scores,
+    /// e.g. `Best 12,345,678 (+3.21%) ▁▂▂▅▇█`.
+    pub fn render(&self, width: usize) -> String {
+        let Some(best) = self.best else {
+            return "No score yet".to_string();
+        };
+        let mut text = format!("Best {}", format_score(best));
+        if let Some(improvement) = self
+            .previous_best
+            .and_then(|previous_best| improvement(previous_best, best))
+        {
+            text.push_str(&format!(" ({})", format_improvement(improvement)));
+        }
+        let sparkline_width = width.saturating_sub(console::measure_text_width(&text) + 1);
+        if sparkline_width > 0 {
+            text.push(' ');
+            text.push_str(&sparkline(&self.scores, sparkline_width));
+        }
+        text
+    }
+}
+
+fn improvement(previous: u64, current: u64) -> Option<f64> {
+    (previous > 0).then(|| (current as f64 - previous as f64) / previous as f64)
+}
+
+pub fn format_improvement(improvement: f64) -> String {
+    format!("{:+.2}%", improvement * 100.0)
+}
+
+/// Formats a score with thousands separators, e.g. `12,345,678`.
+pub fn format_score(score: u64) -> String {
+    let digits = score.to_string();
+    let mut formatted = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+    formatted
+}
+
+/// Draws the last `width` values as a bar chart, scaled between their minimum and maximum.
+fn sparkline(values: &[u64], width: usize) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let values = &values[values.len().saturating_sub(width)..];
+    let (Some(&min), Some(&max)) = (values.iter().min(), values.iter().max()) else {
+        return String::new();
+    };
+    values
+        .iter()
+        .map(|&value| {
+            if max == min {
+                BARS[BARS.len() - 1]
+            } else {
+                let level = (value - min) as f64 / (max - min) as f64;
+                BARS[(level * (BARS.len() - 1) as f64).round() as usize]
+            }
+        })
+        .collect()
+}
+
+/// The result of a task as counted in ABC/ARC-style standings.
+pub struct TaskStats {
+    pub problem: String,
+    pub submissions: usize,
+    pub best_score: u64,
+    /// The number of rejected submissions before the first AC, not counting CE.
+    pub penalties: usize,
+    pub first_accepted: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl TaskStats {
+    /// Collects the stats of each task, ordered by the problem name.
+    pub fn collect<'a>(submissions: impl IntoIterator<Item = &'a Submission>) -> Vec<TaskStats> {
+        let mut by_problem = BTreeMap::<&str, Vec<&Submission>>::new();
+        for submission in submissions {
+            by_problem
+                .entry(&submission.problem)
+                .or_default()
+                .push(submission);
+        }
+
+        by_problem
+            .into_iter()
+            .map(|(problem, mut submissions)| {
+                submissions.sort_by_key(|submission| submission.time);
+                let first_accepted = submissions
+                    .iter()
+                    .position(|submission| submission.status == SubmissionStatus::Accepted);
+                let penalties = submissions[..first_accepted.unwrap_or(submissions.len())]
+                    .iter()
+                    .filter(|submission| {
+                        submission.status.is_rejected()
+                            && submission.status != SubmissionStatus::CompileError
+                    })
+                    .count();
+                let best_score = submissions
+                    .iter()
+                    .filter(|submission| !submission.status.is_pending())
+                    .map(|submission| submission.score)
+                    .max()
+                    .unwrap_or(0);
+                TaskStats {
+                    problem: problem.to_string(),
+                    submissions: submissions.len(),
+                    best_score,
+                    penalties,
+                    first_accepted: first_accepted.map(|index| submissions[index].time),
+                }
+            })
+            .collect()
+    }
+}
+
+/// The estimated time in the standings: the last first AC plus a fixed time per penalty.
+/// Only the penalties of solved tasks count, as in the standings.
+pub struct Penalty {
+    pub count: usize,
+    pub last_accepted: Option<chrono::Duration>,
+    pub per_penalty: chrono::Duration,
+}
+
+impl Penalty {
+    pub fn new(
+        tasks: &[TaskStats],
+        start_time: Option<chrono::DateTime<chrono::Utc>>,
+        per_penalty: chrono::Duration,
+    ) -> Penalty {
+        let solved = tasks.iter().filter(|task| task.first_accepted.is_some());
+        Penalty {
+            count: solved.clone().map(|task| task.penalties).sum(),
+            last_accepted: start_time.and_then(|start_time| {
+                solved
+                    .filter_map(|task| task.first_accepted)
+                    .max()
+                    .map(|time| time - start_time)
+            }),
+            per_penalty,
+        }
+    }
+
+    pub fn total(&self) -> Option<chrono::Duration> {
+        self.last_accepted
+            .map(|last_accepted| last_accepted + self.per_penalty * self.count as i32)
+    }
+}
+
+/// Formats a time from the contest start as in the standings, e.g. `105:07`.
+pub fn format_elapsed(elapsed: chrono::Duration) -> String {
+    let seconds = elapsed.num_seconds().max(0);
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// Renders the penalty and the per-task table below it, fitting in `width`.
+pub fn render_tasks(
+    tasks: &[TaskStats],
+    start_time: Option<chrono::DateTime<chrono::Utc>>,
+    per_penalty: chrono::Duration,
+    width: usize,
+) -> Vec<String> {
+    use console::Alignment;
+    use termion::color::{Fg, LightBlack, Reset, Yellow};
+
+    // Score, first AC, penalty and submissions take 16, 10, 9 and 13 columns.
+    let problem_width = width.saturating_sub(1 + 16 + 10 + 9 + 13).clamp(12, 40);
+
+    let penalty = Penalty::new(tasks, start_time, per_penalty);
+    let mut total = format!(
+        " Penalty: {} × {}min",
+        penalty.count,
+        penalty.per_penalty.num_minutes()
+    );
+    if let (Some(last_accepted), Some(estimated)) = (penalty.last_accepted, penalty.total()) {
+        total.push_str(&format!(
+            " | Last AC: {} | Estimated time: {}",
+            format_elapsed(last_accepted),
+            format_elapsed(estimated)
+        ));
+    }
+
+    let mut lines = vec![total, String::new()];
+    lines.push(format!(
+        "{} {} {} {} {} {}{}",
+        Fg(LightBlack),
+        super::columns::fit("Task", problem_width, Alignment::Left),
+        super::columns::fit("Score", 15, Alignment::Right),
+        super::columns::fit("First AC", 9, Alignment::Right),
+        super::columns::fit("Penalty", 8, Alignment::Right),
+        super::columns::fit("Submissions", 12, Alignment::Right),
+        Fg(Reset)
+    ));
+    for task in tasks {
+        let (color, first_accepted) = match task.first_accepted {
+            Some(time) => (
+                super::status_color(SubmissionStatus::Accepted),
+                start_time.map_or("AC".to_string(), |start_time| {
+                    format_elapsed(time - start_time)
+                }),
+            ),
+            None => (Fg(Yellow).to_string(), "-".to_string()),
+        };
+        let penalties = if task.penalties > 0 {
+            format!("({})", task.penalties)
+        } else {
+            "-".to_string()
+        };
+        lines.push(format!(
+            " {} {}{}{} {} {} {}",
+            super::columns::fit(&task.problem, problem_width, Alignment::Left),
+            color,
+            super::columns::fit(
+                &format!("{}pts", format_score(task.best_score)),
+                15,
+                Alignment::Right
+            ),
+            Fg(Reset),
+            super::columns::fit(&first_accepted, 9, Alignment::Right),
+            super::columns::fit(&penalties, 8, Alignment::Right),
+            super::columns::fit(&task.submissions.to_string(), 12, Alignment::Right),
+        ));
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn submission(minute: i64, problem: &str, status: SubmissionStatus) -> Submission {
+        Submission {
+            time: chrono::DateTime::UNIX_EPOCH + chrono::Duration::minutes(minute),
+            problem: problem.to_string(),
+            task: String::new(),
+            language: "Rust".to_string(),
+            score: if status == SubmissionStatus::Accepted {
+                100
+            } else {
+                0
+            },
+            code_size: "1024 Byte".to_string(),
+            status,
+            execution_time: None,
+            memory: None,
+            detail: String::new(),
+        }
+    }
+
+    #[test]
+    fn penalties_count_the_whole_history() {
+        // More rejections than a page of submissions holds, newest first as on the site.
+        let mut submissions = vec![
+            submission(40, "A - Foo", SubmissionStatus::WrongAnswer),
+            submission(30, "A - Foo", SubmissionStatus::Accepted),
+        ];
+        submissions.extend((0..25).rev().map(|minute| {
+            let status = if minute == 0 {
+                SubmissionStatus::CompileError
+            } else {
+                SubmissionStatus::WrongAnswer
+            };
+            submission(minute, "A - Foo", status)
+        }));
+        submissions.push(submission(
+            5,
+            "B - Bar",
+            SubmissionStatus::TimeLimitExceeded,
+        ));
+
+        let tasks = TaskStats::collect(&submissions);
+        assert_eq!(tasks.len(), 2);
+        let (a, b) = (&tasks[0], &tasks[1]);
+        assert_eq!(a.submissions, 27);
+        // Neither the compile error nor the rejection after the AC counts.
+        assert_eq!(a.penalties, 24);
+        assert_eq!(a.best_score, 100);
+        assert_eq!(
+            a.first_accepted,
+            Some(chrono::DateTime::UNIX_EPOCH + chrono::Duration::minutes(30))
+        );
+        assert_eq!((b.penalties, b.first_accepted), (1, None));
+
+        // Only solved tasks count in the standings.
+        let penalty = Penalty::new(
+            &tasks,
+            Some(chrono::DateTime::UNIX_EPOCH),
+            chrono::Duration::minutes(5),
+        );
+        assert_eq!(penalty.count, 24);
+        assert_eq!(penalty.last_accepted, Some(chrono::Duration::minutes(30)));
+    }
+}
@@ -0,0 +1,145 @@
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// The terminal attributes before entering raw mode.
+static ORIGINAL_TERMIOS: std::sync::Mutex<Option<libc::termios>> = std::sync::Mutex::new(None);
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+static GENERATION: AtomicUsize = AtomicUsize::new(0);
+
+/// Reports button presses and wheel scrolls in the SGR encoding, which has no coordinate limit.
+const ENABLE_MOUSE: &str = "\x1b[?1000h\x1b[?1006h";
+const DISABLE_MOUSE: &str = "\x1b[?1006l\x1b[?1000l";
+
+/// Restores the terminal when dropped.
+pub struct Guard;
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        leave();
+    }
+}
+
+/// Enters raw mode and the alternate screen, and restores them on panic.
+pub fn enter() -> anyhow::Result<Guard> {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        leave();
+        default_hook(info);
+    }));
+
+    activate()?;
+    Ok(Guard)
+}
+
+/// Returns false while the terminal is restored, e.g. when suspended.
+pub fn is_active() -> bool {
+    ACTIVE.load(Ordering::Relaxed)
+}
+
+/// Returns a number that changes whenever the screen is entered again,
+/// meaning its previous contents are gone.
+pub fn generation() -> usize {
+    GENERATION.load(Ordering::Relaxed)
+}
+
+fn activate() -> anyhow::Result<()> {
+    let fd = libc::STDOUT_FILENO;
+    let mut termios = unsafe { std::mem::zeroed::<libc::termios>() };
+    if unsafe { libc::tcgetattr(fd, &mut termios) } != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    ORIGINAL_TERMIOS.lock().unwrap().get_or_insert(termios);
+
+    unsafe { libc::cfmakeraw(&mut termios) };
+    if unsafe { libc::tcsetattr(fd, libc::TCSADRAIN, &termios) } != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+
+    let mut stdout = std::io::stdout();
+    write!(
+        stdout,
+        "{}{}{}",
+        termion::screen::ToAlternateScreen,
+        termion::cursor::Hide,
+        ENABLE_MOUSE
+    )?;
+    stdout.flush()?;
+
+    ACTIVE.store(true, Ordering::Relaxed);
+    GENERATION.fetch_add(1, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Restores the cursor, the main screen and the original terminal attributes.
+/// Does nothing if the terminal is already restored.
+pub fn leave() {
+    if !ACTIVE.swap(false, Ordering::Relaxed) {
+        return;
+    }
+
+    let mut stdout = std::io::stdout();
+    let _ = write!(
+        stdout,
+        "{}{}{}{}",
+        DISABLE_MOUSE,
+        termion::style::Reset,
+        termion::cursor::Show,
+        termion::screen::ToMainScreen
+    );
+    let _ = stdout.flush();
+
+    if let Some(termios) = ORIGINAL_TERMIOS.lock().unwrap().as_ref() {
+        unsafe { libc::tcsetattr(libc::STDOUT_FILENO, libc::TCSADRAIN, termios) };
+    }
+}
+
+/// Restores the terminal and stops the process.
+/// The TUI is entered again by `resume` on the SIGCONT sent when the process is continued.
+pub fn suspend() {
+    leave();
+    unsafe { libc::raise(libc::SIGSTOP) };
+}
+
+/// Enters the TUI again after the process has been continued.
+/// The shell may have reset the terminal while stopped, so this is applied even if active.
+pub fn resume() -> anyhow::Result<()> {
+    activate()
+}
+
+/// Sends SIGTSTP to this process, as Ctrl-Z does not generate it in raw mode.
+pub fn request_suspend() {
+    unsafe { libc::raise(libc::SIGTSTP) };
+}
+
+/// Reads from stdin, returning `None` if nothing arrives within the timeout.
+/// Unlike `tokio::io::stdin`, this never leaves a blocking read behind,
+/// which would keep the runtime from shutting down after a signal.
+pub async fn read_input(timeout: std::time::Duration) -> std::io::Result<Option<Vec<u8>>> {
+    tokio::task::spawn_blocking(move || {
+        let mut fds = libc::pollfd {
+            fd: libc::STDIN_FILENO,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let ready = unsafe { libc::poll(&mut fds, 1, timeout.as_millis() as _) };
+        if ready < 0 {
+            let err = std::io::Error::last_os_error();
+            if err.kind() == std::io::ErrorKind::Interrupted {
+                return Ok(None);
+            }
+            return Err(err);
+        }
+        if ready == 0 {
+            return Ok(None);
+        }
+
+        let mut buf = [0u8; 64];
+        let read = unsafe { libc::read(libc::STDIN_FILENO, buf.as_mut_ptr() as _, buf.len()) };
+        match read {
+            0 => Err(std::io::ErrorKind::UnexpectedEof.into()),
+            read if read < 0 => Err(std::io::Error::last_os_error()),
+            read => Ok(Some(buf[..read as usize].to_vec())),
+        }
+    })
+    .await?
+}
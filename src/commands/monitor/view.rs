@@ -0,0 +1,311 @@
+use super::{input::Key, Submission, SubmissionStatus};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::Display)]
+pub enum VerdictFilter {
+    #[strum(serialize = "AC only")]
+    Accepted,
+    #[strum(serialize = "failures only")]
+    Rejected,
+    #[strum(serialize = "pending only")]
+    Pending,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, strum::Display)]
+pub enum SortKey {
+    #[default]
+    #[strum(serialize = "time")]
+    Time,
+    #[strum(serialize = "problem")]
+    Problem,
+    #[strum(serialize = "score")]
+    Score,
+    #[strum(serialize = "exec time")]
+    ExecutionTime,
+    #[strum(serialize = "memory")]
+    Memory,
+    #[strum(serialize = "verdict")]
+    Verdict,
+}
+
+impl SortKey {
+    fn next(self) -> SortKey {
+        match self {
+            SortKey::Time => SortKey::Problem,
+            SortKey::Problem => SortKey::Score,
+            SortKey::Score => SortKey::ExecutionTime,
+            SortKey::ExecutionTime => SortKey::Memory,
+            SortKey::Memory => SortKey::Verdict,
+            SortKey::Verdict => SortKey::Time,
+        }
+    }
+}
+
+/// The order of the submission list. Ties keep the submission order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Sort {
+    pub key: SortKey,
+    pub descending: bool,
+}
+
+impl Sort {
+    pub fn is_default(&self) -> bool {
+        *self == Sort::default()
+    }
+
+    pub fn cycle_key(&mut self) {
+        self.key = self.key.next();
+    }
+
+    pub fn toggle_order(&mut self) {
+        self.descending = !self.descending;
+    }
+
+    fn compare(&self, a: &Submission, b: &Submission) -> std::cmp::Ordering {
+        let ordering = match self.key {
+            SortKey::Time => a.time.cmp(&b.time),
+            SortKey::Problem => a.problem.cmp(&b.problem),
+            SortKey::Score => a.score.cmp(&b.score),
+            SortKey::ExecutionTime => a.execution_time_ms().cmp(&b.execution_time_ms()),
+            SortKey::Memory => a.memory_kib().cmp(&b.memory_kib()),
+            SortKey::Verdict => a.status.cmp(&b.status),
+        };
+        if self.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+
+    /// Describes the order, e.g. `score ↓`.
+    pub fn describe(&self) -> String {
+        format!("{} {}", self.key, if self.descending { "↓" } else { "↑" })
+    }
+}
+
+/// Conditions a submission must meet to be listed.
+#[derive(Debug, Default)]
+pub struct Filter {
+    /// The task label, e.g. `A` or `Ex`.
+    pub task: Option<String>,
+    pub verdict: Option<VerdictFilter>,
+    pub language: Option<String>,
+    /// Text searched for in the problem name, case-insensitively.
+    pub text: String,
+}
+
+impl Filter {
+    pub fn is_empty(&self) -> bool {
+        self.task.is_none()
+            && self.verdict.is_none()
+            && self.language.is_none()
+            && self.text.is_empty()
+    }
+
+    pub fn matches(&self, submission: &Submission) -> bool {
+        if let Some(task) = &self.task {
+            if submission.label() != task {
+                return false;
+            }
+        }
+        if let Some(verdict) = self.verdict {
+            let matches = match verdict {
+                VerdictFilter::Accepted => submission.status == SubmissionStatus::Accepted,
+                VerdictFilter::Rejected => submission.status.is_rejected(),
+                VerdictFilter::Pending => submission.status.is_pending(),
+            };
+            if !matches {
+                return false;
+            }
+        }
+        if let Some(language) = &self.language {
+            if &submission.language != language {
+                return false;
+            }
+        }
+        submission
+            .problem
+            .to_lowercase()
+            .contains(&self.text.to_lowercase())
+    }
+
+    pub fn cycle_verdict(&mut self) {
+        self.verdict = match self.verdict {
+            None => Some(VerdictFilter::Accepted),
+            Some(VerdictFilter::Accepted) => Some(VerdictFilter::Rejected),
+            Some(VerdictFilter::Rejected) => Some(VerdictFilter::Pending),
+            Some(VerdictFilter::Pending) => None,
+        };
+    }
+
+    /// Switches to the next language in `languages`, or clears the filter after the last one.
+    pub fn cycle_language(&mut self, languages: &[String]) {
+        let next = match &self.language {
+            Some(language) => languages
+                .iter()
+                .position(|candidate| candidate == language)
+                .map_or(0, |index| index + 1),
+            None => 0,
+        };
+        self.language = languages.get(next).cloned();
+    }
+
+    /// Switches to the next task in `labels` starting with `letter`, e.g. from `E` to `Ex`,
+    /// or clears the filter after the last one.
+    pub fn cycle_task(&mut self, letter: char, labels: &[String]) {
+        let letter = letter.to_string();
+        let mut candidates = labels
+            .iter()
+            .filter(|label| label.starts_with(&letter))
+            .collect::<Vec<_>>();
+        // Without submissions to the task, the letter is still filtered on.
+        if candidates.is_empty() {
+            candidates.push(&letter);
+        }
+        let next = match &self.task {
+            Some(task) => candidates
+                .iter()
+                .position(|candidate| *candidate == task)
+                .map_or(0, |index| index + 1),
+            None => 0,
+        };
+        self.task = candidates.get(next).map(|label| label.to_string());
+    }
+
+    /// Describes the active conditions, e.g. `A, AC only, "sum"`.
+    pub fn describe(&self) -> String {
+        let mut conditions = vec![];
+        if let Some(task) = &self.task {
+            conditions.push(task.clone());
+        }
+        if let Some(verdict) = self.verdict {
+            conditions.push(verdict.to_string());
+        }
+        if let Some(language) = &self.language {
+            conditions.push(language.clone());
+        }
+        if !self.text.is_empty() {
+            conditions.push(format!("{:?}", self.text));
+        }
+        conditions.join(", ")
+    }
+}
+
+/// The diff between two submissions, shown over the list.
+pub struct Diff {
+    /// The submissions compared, the older one first.
+    pub ids: (u64, u64),
+    /// The lines of the unified diff, or `None` while the sources are being fetched.
+    pub lines: Option<Vec<String>>,
+    /// The index of the first visible line.
+    pub scroll: usize,
+}
+
+/// The selected row and the part of the submission list that is on screen.
+#[derive(Default)]
+pub struct View {
+    /// The selected submission, or `None` to follow the newest one.
+    pub selected: Option<u64>,
+    /// The index of the first visible row.
+    scroll: usize,
+    /// The number of rows the list had when it was last drawn.
+    pub height: usize,
+    pub filter: Filter,
+    pub sort: Sort,
+    /// Whether keys are being typed into the search text.
+    pub searching: bool,
+    /// Whether the per-task summary is shown instead of the list.
+    pub tasks: bool,
+    /// Whether the help is shown over the list.
+    pub help: bool,
+    /// The diff shown over the list, if any.
+    pub diff: Option<Diff>,
+    /// The screen row (0-based) of the first visible row when the list was last drawn.
+    pub list_top: usize,
+    /// The number of rows drawn in the list, which may be less than `height`.
+    pub list_rows: usize,
+    /// The screen row (0-based) of the footer, and the columns of the hints that can be clicked.
+    pub footer_row: usize,
+    pub footer_hints: Vec<(std::ops::Range<usize>, Key)>,
+    /// The row index and time of the last click, to detect double-clicks.
+    pub last_click: Option<(usize, std::time::Instant)>,
+}
+
+impl View {
+    /// Returns the ids of the submissions to list, in display order.
+    pub fn visible_ids(&self, submissions: &indexmap::IndexMap<u64, Submission>) -> Vec<u64> {
+        let mut visible = submissions
+            .iter()
+            .filter(|(_, submission)| self.filter.matches(submission))
+            .collect::<Vec<_>>();
+        if !self.sort.is_default() {
+            visible.sort_by(|(_, a), (_, b)| self.sort.compare(a, b));
+        }
+        visible.into_iter().map(|(id, _)| *id).collect()
+    }
+
+    /// Returns the index of the selected row in `ids`.
+    pub fn selected_index(&self, ids: &[u64]) -> Option<usize> {
+        let last = ids.len().checked_sub(1);
+        match self.selected {
+            Some(selected) => ids.iter().position(|&id| id == selected).or(last),
+            None => last,
+        }
+    }
+
+    /// Moves the selection by `delta` rows, following the newest row when reaching the end.
+    pub fn move_selection(&mut self, ids: &[u64], delta: isize) {
+        let Some(index) = self.selected_index(ids) else {
+            return;
+        };
+        let index = index.saturating_add_signed(delta).min(ids.len() - 1);
+        self.select_index(ids, index);
+    }
+
+    pub fn select_index(&mut self, ids: &[u64], index: usize) {
+        self.selected = if index + 1 >= ids.len() {
+            None
+        } else {
+            Some(ids[index])
+        };
+    }
+
+    pub fn select_first(&mut self, ids: &[u64]) {
+        self.select_index(ids, 0);
+    }
+
+    pub fn select_last(&mut self) {
+        self.selected = None;
+    }
+
+    /// Returns the index in the visible ids of the row drawn at screen row `y` (0-based).
+    pub fn index_at_row(&self, y: usize) -> Option<usize> {
+        let row = y.checked_sub(self.list_top)?;
+        (row < self.list_rows).then_some(self.scroll + row)
+    }
+
+    /// Returns the key of the footer hint drawn at screen column `x` (0-based).
+    pub fn footer_hint_at(&self, x: usize) -> Option<Key> {
+        self.footer_hints
+            .iter()
+            .find(|(range, _)| range.contains(&x))
+            .map(|(_, key)| *key)
+    }
+
+    /// Scrolls the list so that the selection is visible, and returns the range of visible rows.
+    pub fn visible_range(&mut self, ids: &[u64], height: usize) -> std::ops::Range<usize> {
+        self.height = height;
+        let max_scroll = ids.len().saturating_sub(height);
+        if let Some(index) = self.selected_index(ids) {
+            if index < self.scroll {
+                self.scroll = index;
+            } else if index >= self.scroll + height {
+                self.scroll = index + 1 - height;
+            }
+        }
+        if self.selected.is_none() {
+            self.scroll = max_scroll;
+        }
+        self.scroll = self.scroll.min(max_scroll);
+        self.scroll..(self.scroll + height).min(ids.len())
+    }
+}
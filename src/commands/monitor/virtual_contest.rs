@@ -0,0 +1,87 @@
+use chrono::{DateTime, Local, NaiveDateTime, NaiveTime, TimeZone, Utc};
+
+/// Parses the start of a virtual contest: `now`, an RFC 3339 time,
+/// a local `YYYY-MM-DD HH:MM[:SS]`, or a local `HH:MM[:SS]` today.
+pub fn parse_start(text: &str) -> anyhow::Result<DateTime<Utc>> {
+    let text = text.trim();
+    if text == "now" {
+        return Ok(Utc::now());
+    }
+    if let Ok(time) = DateTime::parse_from_rfc3339(text) {
+        return Ok(time.to_utc());
+    }
+
+    let local = [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+    .or_else(|| {
+        ["%H:%M:%S", "%H:%M"]
+            .iter()
+            .find_map(|format| NaiveTime::parse_from_str(text, format).ok())
+            .map(|time| Local::now().date_naive().and_time(time))
+    })
+    .ok_or_else(|| anyhow::anyhow!("Invalid virtual start time: {:?}", text))?;
+    Local
+        .from_local_datetime(&local)
+        .earliest()
+        .map(|time| time.to_utc())
+        .ok_or_else(|| anyhow::anyhow!("Virtual start time does not exist: {:?}", text))
+}
+
+/// Parses a duration such as `100m`, `1h40m`, `90s` or `100` (minutes).
+pub fn parse_duration(text: &str) -> anyhow::Result<chrono::Duration> {
+    let text = text.trim();
+    if let Ok(minutes) = text.parse::<i64>() {
+        anyhow::ensure!(minutes > 0, "Virtual duration must be positive: {:?}", text);
+        return Ok(chrono::Duration::minutes(minutes));
+    }
+
+    let mut total = chrono::Duration::zero();
+    let mut number = String::new();
+    for c in text.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let value = number
+            .parse::<i64>()
+            .map_err(|_| anyhow::anyhow!("Invalid virtual duration: {:?}", text))?;
+        number.clear();
+        total += match c {
+            'h' => chrono::Duration::hours(value),
+            'm' => chrono::Duration::minutes(value),
+            's' => chrono::Duration::seconds(value),
+            _ => anyhow::bail!("Invalid virtual duration: {:?}", text),
+        };
+    }
+    anyhow::ensure!(
+        number.is_empty() && total > chrono::Duration::zero(),
+        "Invalid virtual duration: {:?}",
+        text
+    );
+    Ok(total)
+}
+
+/// Renders the timer for the header, e.g. `Virtual 45:12 elapsed, 54:48 left`.
+pub fn render_timer(start_time: DateTime<Utc>, end_time: DateTime<Utc>) -> String {
+    let now = Utc::now();
+    if now < start_time {
+        format!(
+            "Virtual contest starts in {}",
+            super::summary::format_elapsed(start_time - now)
+        )
+    } else if now < end_time {
+        format!(
+            "Virtual {} elapsed, {} left",
+            super::summary::format_elapsed(now - start_time),
+            super::summary::format_elapsed(end_time - now)
+        )
+    } else {
+        "Virtual contest ended".to_string()
+    }
+}
@@ -0,0 +1,43 @@
+use crate::{config, error, info, warn, webhook::VerdictEvent};
+
+pub async fn main() -> anyhow::Result<()> {
+    let config = config::load()?;
+    if config.monitor.webhooks.is_empty() {
+        warn!("No webhooks configured");
+        return Ok(());
+    }
+
+    let event = VerdictEvent {
+        id: 0,
+        contest: "AtCoder Beginner Contest 300".to_string(),
+        contest_url: "https://atcoder.jp/contests/abc300".to_string(),
+        problem: "A - N-choice question".to_string(),
+        status: "AC".to_string(),
+        previous_status: Some("WJ".to_string()),
+        score: 100,
+        language: "Rust (rustc 1.70.0)".to_string(),
+        time: chrono::Utc::now(),
+        execution_time_ms: Some(1),
+        memory_kib: Some(2048),
+        url: "https://atcoder.jp/contests/abc300/submissions/0".to_string(),
+    };
+    let client = reqwest::Client::new();
+    let mut failed = 0;
+    for webhook in &config.monitor.webhooks {
+        match crate::webhook::send(&client, webhook, &event).await {
+            Ok(()) => info!("Sent a test event to {}", webhook.url),
+            Err(err) => {
+                error!("Failed to send a test event to {}: {}", webhook.url, err);
+                failed += 1;
+            }
+        }
+    }
+    anyhow::ensure!(
+        failed == 0,
+        "{} of {} webhooks failed",
+        failed,
+        config.monitor.webhooks.len()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,22 @@
+use crate::{info, session::Session, warn};
+
+pub async fn main() -> anyhow::Result<()> {
+    let session =
+        Session::load().ok_or_else(|| anyhow::anyhow!("Not logged in, cookie file not found"))?;
+
+    let Some(username) = session.current_user().await? else {
+        warn!("Not logged in, the session has expired");
+        return Ok(());
+    };
+    info!("Logged in as {}", username);
+
+    match session.session_expiry() {
+        Some(expiry) => {
+            let expiry = expiry.with_timezone(&chrono::Local);
+            info!("Session expires at {}", expiry.format("%Y-%m-%d %H:%M:%S"));
+        }
+        None => info!("Session expires when the browser session ends"),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,184 @@
+use crate::store;
+
+/// Settings read from `config.json` in the profile directory.
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub monitor: MonitorConfig,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MonitorConfig {
+    /// The columns of the submission list, from left to right.
+    pub columns: Vec<Column>,
+    /// Keys bound to actions, replacing the default keys of each action listed.
+    pub keymap: std::collections::HashMap<Action, Vec<String>>,
+    /// The minutes added to the standings time for each rejected submission.
+    pub penalty_minutes: u32,
+    /// Endpoints notified whenever the verdict of a submission changes.
+    pub webhooks: Vec<WebhookConfig>,
+    /// The format of the file written by the export action.
+    pub export_format: ExportFormat,
+    /// The origin allowed to read the responses of `--serve` from other pages, e.g. `*`.
+    pub serve_allow_origin: Option<String>,
+}
+
+impl Default for MonitorConfig {
+    fn default() -> Self {
+        MonitorConfig {
+            columns: vec![
+                Column::Status,
+                Column::Time,
+                Column::Problem,
+                Column::Score,
+                Column::ExecutionTime,
+                Column::Memory,
+                Column::CodeSize,
+                Column::Language,
+            ],
+            keymap: std::collections::HashMap::new(),
+            penalty_minutes: 5,
+            webhooks: vec![],
+            export_format: ExportFormat::default(),
+            serve_allow_origin: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Column {
+    Status,
+    Time,
+    Problem,
+    Score,
+    #[serde(rename = "exec_time")]
+    ExecutionTime,
+    Memory,
+    CodeSize,
+    Language,
+    Id,
+    /// The time since the contest started.
+    Elapsed,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// The format of the payload. Ignored if `payload` is set.
+    #[serde(default)]
+    pub preset: WebhookPreset,
+    /// A JSON template whose strings may contain placeholders such as `{problem}` and `{status}`.
+    pub payload: Option<serde_json::Value>,
+    /// Seconds to wait for each request.
+    #[serde(default = "default_webhook_timeout")]
+    pub timeout: u64,
+    /// How many times a failed request is retried.
+    #[serde(default = "default_webhook_retries")]
+    pub retries: u32,
+}
+
+fn default_webhook_timeout() -> u64 {
+    10
+}
+
+fn default_webhook_retries() -> u32 {
+    3
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookPreset {
+    /// The event itself as a JSON object.
+    #[default]
+    Json,
+    Discord,
+    Slack,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Json,
+    Markdown,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Markdown => "md",
+        }
+    }
+}
+
+/// An operation in the monitor that can be bound to keys.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Deserialize, strum::EnumIter, strum::Display,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Action {
+    Quit,
+    Suspend,
+    Pause,
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    First,
+    Last,
+    Open,
+    Search,
+    FilterVerdict,
+    FilterLanguage,
+    ClearFilter,
+    Sort,
+    SortOrder,
+    Tasks,
+    Diff,
+    Export,
+    Help,
+}
+
+impl Action {
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::Quit => "Quit",
+            Action::Suspend => "Suspend to the shell",
+            Action::Pause => "Pause or resume polling",
+            Action::Up => "Select the previous submission",
+            Action::Down => "Select the next submission",
+            Action::PageUp => "Scroll up a page",
+            Action::PageDown => "Scroll down a page",
+            Action::First => "Select the oldest submission",
+            Action::Last => "Follow the newest submission",
+            Action::Open => "Open the submission detail",
+            Action::Search => "Search submissions",
+            Action::FilterVerdict => "Cycle the verdict filter",
+            Action::FilterLanguage => "Cycle the language filter",
+            Action::ClearFilter => "Clear filters",
+            Action::Sort => "Cycle the sort key",
+            Action::SortOrder => "Toggle the sort order",
+            Action::Tasks => "Toggle the per-task summary",
+            Action::Diff => "Diff against the previous submission to the task",
+            Action::Export => "Export the submissions to a file",
+            Action::Help => "Show this help",
+        }
+    }
+}
+
+/// Loads the config of the current profile, falling back to the defaults if there is none.
+pub fn load() -> anyhow::Result<Config> {
+    let path = store::get_config_path();
+    let Ok(content) = fs_err::read_to_string(&path) else {
+        return Ok(Config::default());
+    };
+    serde_json::from_str(&content)
+        .map_err(|err| anyhow::anyhow!("Failed to parse {}: {}", path.display(), err))
+}
@@ -0,0 +1,255 @@
+use std::path::{Path, PathBuf};
+
+use crate::debug;
+
+/// The contest (and optionally the task) the user is working on.
+#[derive(Debug, Clone)]
+pub struct Context {
+    pub contest_url: String,
+    /// The screen name of the task, e.g. `abc300_a`.
+    pub task: Option<String>,
+}
+
+impl Context {
+    /// Builds a context from a contest id, a contest URL or a task URL.
+    pub fn from_arg(arg: &str) -> Context {
+        if let Some(context) = parse_atcoder_url(arg) {
+            return context;
+        }
+        let contest_url = if arg.starts_with("https://") {
+            arg.trim_end_matches('/').to_string()
+        } else {
+            format!("https://atcoder.jp/contests/{}", arg)
+        };
+        Context {
+            contest_url,
+            task: None,
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct AccContest {
+    contest: AccContestInfo,
+    #[serde(default)]
+    tasks: Vec<AccTask>,
+}
+
+#[derive(serde::Deserialize)]
+struct AccContestInfo {
+    id: String,
+    url: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+struct AccTask {
+    id: String,
+    directory: Option<AccTaskDirectory>,
+}
+
+#[derive(serde::Deserialize)]
+struct AccTaskDirectory {
+    path: String,
+}
+
+#[derive(serde::Deserialize)]
+struct OjHistoryEntry {
+    directory: PathBuf,
+    url: String,
+}
+
+/// Infers the context by walking up from `cwd`, looking for atcoder-cli's `contest.acc.json`
+/// or a directory online-judge-tools downloaded tests in.
+/// Falls back to the name of `cwd` as the contest id, and returns `None` if it has none.
+pub fn resolve(cwd: &Path) -> Option<Context> {
+    resolve_with_history(cwd, &load_oj_history())
+}
+
+fn resolve_with_history(cwd: &Path, oj_history: &[OjHistoryEntry]) -> Option<Context> {
+    for dir in cwd.ancestors() {
+        if let Some(context) = resolve_acc(dir, cwd) {
+            debug!("Found contest.acc.json in {:?}", dir);
+            return Some(context);
+        }
+        if let Some(context) = resolve_oj(dir, oj_history) {
+            debug!("Found online-judge-tools metadata in {:?}", dir);
+            return Some(context);
+        }
+    }
+
+    // The root and non-UTF-8 names cannot be a contest id.
+    let contest_id = cwd.file_name()?.to_str()?;
+    Some(Context::from_arg(contest_id))
+}
+
+fn resolve_acc(dir: &Path, cwd: &Path) -> Option<Context> {
+    let path = dir.join("contest.acc.json");
+    let content = fs_err::read_to_string(&path).ok()?;
+    let acc: AccContest = match serde_json::from_str(&content) {
+        Ok(acc) => acc,
+        Err(err) => {
+            debug!("Failed to parse {:?}: {}", path, err);
+            return None;
+        }
+    };
+
+    let task_dir = cwd.strip_prefix(dir).ok()?.components().next();
+    let task = task_dir.and_then(|task_dir| {
+        acc.tasks
+            .iter()
+            .find(|task| {
+                task.directory
+                    .as_ref()
+                    .is_some_and(|directory| Path::new(&directory.path) == Path::new(&task_dir))
+            })
+            .map(|task| task.id.clone())
+    });
+
+    Some(Context {
+        contest_url: acc
+            .contest
+            .url
+            .unwrap_or_else(|| format!("https://atcoder.jp/contests/{}", acc.contest.id)),
+        task,
+    })
+}
+
+/// `oj download` records the directory it was run in, which holds its `test` directory.
+fn resolve_oj(dir: &Path, history: &[OjHistoryEntry]) -> Option<Context> {
+    history
+        .iter()
+        .rev()
+        .find(|entry| entry.directory == dir)
+        .and_then(|entry| parse_atcoder_url(&entry.url))
+}
+
+fn load_oj_history() -> Vec<OjHistoryEntry> {
+    let Some(mut path) = dirs::cache_dir() else {
+        return vec![];
+    };
+    path.push("online-judge-tools");
+    path.push("download-history.jsonl");
+    let Ok(content) = fs_err::read_to_string(&path) else {
+        return vec![];
+    };
+    content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+fn parse_atcoder_url(url: &str) -> Option<Context> {
+    let regex =
+        regex::Regex::new(r"^https://atcoder\.jp/contests/([^/?#]+)(?:/tasks/([^/?#]+))?").unwrap();
+    let captures = regex.captures(url)?;
+    Some(Context {
+        contest_url: format!("https://atcoder.jp/contests/{}", &captures[1]),
+        task: captures.get(2).map(|task| task.as_str().to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates an empty directory for a test.
+    fn temp_dir(name: &str) -> PathBuf {
+        // Resolving logs what it found.
+        static LOG: std::sync::Once = std::sync::Once::new();
+        LOG.call_once(crate::log::init);
+
+        let dir = std::env::temp_dir().join(format!(
+            "atcoder-judge-monitor-context-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs_err::remove_dir_all(&dir);
+        fs_err::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn resolve_acc_layout() {
+        let dir = temp_dir("acc");
+        let contest_dir = dir.join("abc300");
+        fs_err::create_dir_all(contest_dir.join("a").join("test")).unwrap();
+        fs_err::write(
+            contest_dir.join("contest.acc.json"),
+            serde_json::json!({
+                "contest": {
+                    "id": "abc300",
+                    "title": "AtCoder Beginner Contest 300",
+                    "url": "https://atcoder.jp/contests/abc300",
+                },
+                "tasks": [
+                    {
+                        "id": "abc300_a",
+                        "label": "A",
+                        "directory": { "path": "a", "testdir": "test", "submit": "main.rs" },
+                    },
+                    { "id": "abc300_b", "label": "B" },
+                ],
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let context = resolve_with_history(&contest_dir, &[]).unwrap();
+        assert_eq!(context.contest_url, "https://atcoder.jp/contests/abc300");
+        assert_eq!(context.task, None);
+
+        // Any directory below the task directory is in the task.
+        let context = resolve_with_history(&contest_dir.join("a").join("test"), &[]).unwrap();
+        assert_eq!(context.contest_url, "https://atcoder.jp/contests/abc300");
+        assert_eq!(context.task.as_deref(), Some("abc300_a"));
+
+        fs_err::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn resolve_oj_layout() {
+        let dir = temp_dir("oj");
+        let task_dir = dir.join("abc300_a");
+        fs_err::create_dir_all(task_dir.join("src")).unwrap();
+        // As written to `download-history.jsonl`, with the directory `oj d` was run in.
+        // The latest download to the directory wins.
+        let history = [
+            (
+                "https://atcoder.jp/contests/abc299/tasks/abc299_a",
+                &task_dir,
+            ),
+            (
+                "https://atcoder.jp/contests/abc300/tasks/abc300_a",
+                &task_dir,
+            ),
+            ("https://atcoder.jp/contests/abc300/tasks/abc300_b", &dir),
+        ]
+        .map(|(url, directory)| {
+            let line =
+                serde_json::json!({ "timestamp": 1682168400, "directory": directory, "url": url });
+            serde_json::from_value::<OjHistoryEntry>(line).unwrap()
+        });
+
+        for cwd in [task_dir.clone(), task_dir.join("src")] {
+            let context = resolve_with_history(&cwd, &history).unwrap();
+            assert_eq!(context.contest_url, "https://atcoder.jp/contests/abc300");
+            assert_eq!(context.task.as_deref(), Some("abc300_a"));
+        }
+
+        fs_err::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn resolve_falls_back_to_directory_name() {
+        let dir = temp_dir("fallback");
+        let contest_dir = dir.join("arc150");
+        fs_err::create_dir_all(&contest_dir).unwrap();
+
+        let context = resolve_with_history(&contest_dir, &[]).unwrap();
+        assert_eq!(context.contest_url, "https://atcoder.jp/contests/arc150");
+        assert_eq!(context.task, None);
+        assert!(resolve_with_history(Path::new("/"), &[]).is_none());
+
+        fs_err::remove_dir_all(&dir).unwrap();
+    }
+}
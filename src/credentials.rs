@@ -0,0 +1,206 @@
+use anyhow::Context as _;
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+    ChaCha20Poly1305, Key,
+};
+
+use crate::{debug, store};
+
+const KEYRING_SERVICE: &str = "atcoder-judge-monitor";
+
+/// Set this to `file` to skip the secret service and always use the encrypted file.
+const BACKEND_ENV: &str = "ATCODER_JUDGE_MONITOR_CREDENTIAL_BACKEND";
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Credentials {
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(Debug, Clone, Copy, strum::Display)]
+pub enum Backend {
+    #[strum(serialize = "the system secret service")]
+    Keyring,
+    #[strum(serialize = "an encrypted file")]
+    File,
+}
+
+fn keyring_entry() -> keyring::Result<keyring::Entry> {
+    let profile = store::get_profile();
+    if profile == store::DEFAULT_PROFILE {
+        keyring::Entry::new(KEYRING_SERVICE, "credentials")
+    } else {
+        keyring::Entry::new(KEYRING_SERVICE, &format!("credentials:{}", profile))
+    }
+}
+
+fn use_keyring() -> bool {
+    std::env::var(BACKEND_ENV).map_or(true, |backend| backend != "file")
+}
+
+/// Saves the credentials, preferring the system secret service over the encrypted file.
+pub async fn save(credentials: &Credentials) -> anyhow::Result<Backend> {
+    let json = serde_json::to_string(credentials)?;
+    if use_keyring() {
+        let result = {
+            let json = json.clone();
+            tokio::task::spawn_blocking(move || keyring_entry()?.set_password(&json)).await?
+        };
+        match result {
+            Ok(()) => {
+                let _ = fs_err::remove_file(store::get_credentials_path());
+                return Ok(Backend::Keyring);
+            }
+            Err(err) => debug!("Failed to use the secret service: {}", err),
+        }
+    }
+
+    save_file(&json)?;
+    Ok(Backend::File)
+}
+
+/// Loads the saved credentials, if any.
+pub async fn load() -> Option<Credentials> {
+    if use_keyring() {
+        let result = tokio::task::spawn_blocking(|| keyring_entry()?.get_password())
+            .await
+            .ok()?;
+        match result {
+            Ok(json) => return serde_json::from_str(&json).ok(),
+            Err(err) => debug!("Failed to read from the secret service: {}", err),
+        }
+    }
+
+    match load_file() {
+        Ok(json) => serde_json::from_str(&json).ok(),
+        Err(err) => {
+            debug!("Failed to read credentials file: {}", err);
+            None
+        }
+    }
+}
+
+/// Deletes the saved credentials from every backend.
+pub async fn delete() {
+    if use_keyring() {
+        let _ = tokio::task::spawn_blocking(|| keyring_entry()?.delete_credential()).await;
+    }
+    let _ = fs_err::remove_file(store::get_credentials_path());
+    let _ = fs_err::remove_file(store::get_credentials_key_path());
+}
+
+/// Writes a file that only the current user can read, without exposing it to others in between.
+fn write_private(path: &std::path::Path, contents: &[u8]) -> anyhow::Result<()> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(path)
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+    file.write_all(contents)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+fn load_or_create_key(path: &std::path::Path) -> anyhow::Result<Key> {
+    if let Ok(key) = fs_err::read(path) {
+        anyhow::ensure!(key.len() == 32, "Invalid key file: {:?}", path);
+        return Ok(*Key::from_slice(&key));
+    }
+
+    let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+    write_private(path, &key)?;
+    Ok(key)
+}
+
+fn save_file(json: &str) -> anyhow::Result<()> {
+    write_encrypted(
+        json,
+        &store::get_credentials_path(),
+        &store::get_credentials_key_path(),
+    )
+}
+
+fn write_encrypted(
+    json: &str,
+    path: &std::path::Path,
+    key_path: &std::path::Path,
+) -> anyhow::Result<()> {
+    let cipher = ChaCha20Poly1305::new(&load_or_create_key(key_path)?);
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let encrypted = cipher
+        .encrypt(&nonce, json.as_bytes())
+        .map_err(|err| anyhow::anyhow!("Failed to encrypt credentials: {}", err))?;
+
+    write_private(path, &[nonce.as_slice(), &encrypted].concat())
+}
+
+fn load_file() -> anyhow::Result<String> {
+    read_encrypted(
+        &store::get_credentials_path(),
+        &store::get_credentials_key_path(),
+    )
+}
+
+fn read_encrypted(path: &std::path::Path, key_path: &std::path::Path) -> anyhow::Result<String> {
+    let content = fs_err::read(path)?;
+    anyhow::ensure!(content.len() > 12, "Credentials file is too short");
+    let (nonce, encrypted) = content.split_at(12);
+
+    let cipher = ChaCha20Poly1305::new(&load_or_create_key(key_path)?);
+    let decrypted = cipher
+        .decrypt(chacha20poly1305::Nonce::from_slice(nonce), encrypted)
+        .map_err(|err| anyhow::anyhow!("Failed to decrypt credentials: {}", err))?;
+    String::from_utf8(decrypted).context("Credentials file is not valid UTF-8")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypted_file_round_trip() {
+        let dir = std::env::temp_dir().join(format!(
+            "atcoder-judge-monitor-credentials-{}",
+            std::process::id()
+        ));
+        fs_err::create_dir_all(&dir).unwrap();
+        let (path, key_path) = (dir.join("credentials"), dir.join("credentials.key"));
+
+        let credentials = Credentials {
+            username: "tourist".to_string(),
+            password: "pa55, \"word\"".to_string(),
+        };
+        let json = serde_json::to_string(&credentials).unwrap();
+        write_encrypted(&json, &path, &key_path).unwrap();
+
+        // The password must not be stored in plain text.
+        let content = fs_err::read(&path).unwrap();
+        assert!(!content.windows(4).any(|window| window == b"pa55"));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            for file in [&path, &key_path] {
+                let mode = fs_err::metadata(file).unwrap().permissions().mode();
+                assert_eq!(mode & 0o777, 0o600, "{}", file.display());
+            }
+        }
+
+        let loaded: Credentials =
+            serde_json::from_str(&read_encrypted(&path, &key_path).unwrap()).unwrap();
+        assert_eq!(loaded.username, credentials.username);
+        assert_eq!(loaded.password, credentials.password);
+
+        // Another key cannot decrypt the file.
+        fs_err::remove_file(&key_path).unwrap();
+        assert!(read_encrypted(&path, &key_path).is_err());
+
+        fs_err::remove_dir_all(&dir).unwrap();
+    }
+}
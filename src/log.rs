@@ -58,7 +58,7 @@ pub fn strip_ansi_codes(input: &str) -> String {
     if *ANSI.get().unwrap() {
         return input.to_string();
     }
-    return console::strip_ansi_codes(input).to_string();
+    console::strip_ansi_codes(input).to_string()
 }
 
 pub fn _debug(message: String) {
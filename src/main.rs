@@ -1,6 +1,11 @@
 mod commands;
+mod config;
+mod context;
+mod credentials;
 mod log;
+mod session;
 mod store;
+mod webhook;
 
 use clap::{Parser, Subcommand};
 use std::io::Write;
@@ -10,43 +15,247 @@ use std::io::Write;
 struct Args {
     #[command(subcommand)]
     command: Commands,
+
+    /// The profile to use. Each profile has its own session and settings.
+    #[arg(long, global = true, default_value = store::DEFAULT_PROFILE)]
+    profile: String,
 }
 
 #[derive(Subcommand, Debug)]
 enum Commands {
     /// Login to AtCoder.
-    Login,
+    Login {
+        /// Your username. Defaults to `ATCODER_USERNAME`, or asks interactively.
+        #[arg(long)]
+        username: Option<String>,
+
+        /// Read the password from the first line of stdin.
+        /// The username must then be given by `--username` or `ATCODER_USERNAME`.
+        /// Otherwise `ATCODER_PASSWORD` is used, or the password is asked interactively.
+        #[arg(long)]
+        password_stdin: bool,
+
+        /// Import a `REVEL_SESSION` cookie from your browser instead of logging in.
+        /// Defaults to `ATCODER_REVEL_SESSION`, which is ignored if `--username`, `--password-stdin`
+        /// or `--remember` is given.
+        #[arg(long, conflicts_with_all = ["username", "password_stdin"])]
+        revel_session: Option<String>,
+
+        /// Save your username and password so that the session can be renewed automatically.
+        #[arg(long)]
+        remember: bool,
+    },
+
+    /// Logout from AtCoder and delete the saved session.
+    Logout,
+
+    /// Show the logged-in user and when the session expires.
+    Whoami,
 
     /// Monitor your submission in the contest.
     Monitor {
         /// The URL of the contest you want to monitor.
-        /// If not specified, the tool will infer the contest URL from the current directory.
+        /// If not specified, the tool will infer the contest URL from the current directory,
+        /// using atcoder-cli or online-judge-tools metadata when available.
+        contest_url: Option<String>,
+
+        /// Only monitor submissions to this task (e.g. `abc300_a`).
+        #[arg(long)]
+        task: Option<String>,
+
+        /// Show scores for heuristic contests, with the best score and its trend.
+        /// Enabled automatically for AHC.
+        #[arg(long)]
+        ahc: bool,
+
+        /// Practice the contest as a virtual contest starting at this time:
+        /// `now`, `HH:MM`, `YYYY-MM-DD HH:MM` or an RFC 3339 time.
+        #[arg(long)]
+        virtual_start: Option<String>,
+
+        /// The length of the virtual contest, e.g. `100m` or `1h40m`.
+        /// Defaults to the length of the original contest.
+        #[arg(long, requires = "virtual_start")]
+        virtual_duration: Option<String>,
+
+        /// Serve the submissions on this address, e.g. `127.0.0.1:8080`:
+        /// `/submissions` returns them as JSON and `/events` streams verdict changes as Server-Sent Events.
+        #[arg(long, value_name = "ADDRESS")]
+        serve: Option<std::net::SocketAddr>,
+    },
+
+    /// Export your submissions in the contest to CSV, JSON or a Markdown table.
+    Export {
+        /// The URL of the contest. Inferred from the current directory if not specified.
+        contest_url: Option<String>,
+
+        /// Only export submissions to this task (e.g. `abc300_a`).
+        #[arg(long)]
+        task: Option<String>,
+
+        /// The output format.
+        #[arg(long, value_enum, default_value_t)]
+        format: config::ExportFormat,
+
+        /// The file to write to. Defaults to stdout.
+        #[arg(long, short)]
+        output: Option<std::path::PathBuf>,
+    },
+
+    /// Show the changes between the source code of two submissions.
+    Diff {
+        /// The older submission, e.g. `12345678`.
+        old_id: u64,
+
+        /// The newer submission.
+        new_id: u64,
+
+        /// The URL of the contest. Inferred from the current directory if not specified.
         contest_url: Option<String>,
     },
+
+    /// Download the source code of your submissions in the contest
+    /// into `<contest>/<task>/<id>_<verdict>.<ext>`, skipping files that already exist.
+    FetchSources {
+        /// The URL of the contest. Inferred from the current directory if not specified.
+        contest_url: Option<String>,
+
+        /// Only download submissions to this task (e.g. `abc300_a`).
+        #[arg(long)]
+        task: Option<String>,
+
+        /// Only download accepted submissions.
+        #[arg(long)]
+        accepted_only: bool,
+
+        /// Only download the latest submission to each task.
+        #[arg(long)]
+        latest: bool,
+
+        /// The directory to create the contest directory in.
+        #[arg(long, short, default_value = ".")]
+        output: std::path::PathBuf,
+    },
+
+    /// Send a sample verdict event to the configured webhooks.
+    WebhookTest,
+}
+
+/// Builds the context from the arguments, inferring what is missing from the current directory.
+fn resolve_context(
+    contest_url: Option<String>,
+    task: Option<String>,
+) -> anyhow::Result<context::Context> {
+    let mut context = match contest_url {
+        Some(contest_url) => context::Context::from_arg(&contest_url),
+        None => context::resolve(&std::env::current_dir()?).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Failed to infer the contest from the current directory, pass the contest URL"
+            )
+        })?,
+    };
+    if task.is_some() {
+        context.task = task;
+    }
+    Ok(context)
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let args = Args::parse();
 
-    store::create_config_dir();
-
     log::init();
 
+    if let Err(err) = store::set_profile(args.profile) {
+        error!("{}", err);
+        std::process::exit(1);
+    }
+    store::create_config_dir();
+
     let result = match args.command {
-        Commands::Login => commands::login::main().await,
-        Commands::Monitor { contest_url } => {
-            let contest_id = contest_url.unwrap_or_else(|| {
-                let cwd = std::env::current_dir().unwrap();
-                let contest_id = cwd.file_name().unwrap().to_str().unwrap();
-                contest_id.to_string()
-            });
-            let contest_url = if contest_id.starts_with("https://") {
-                contest_id
-            } else {
-                format!("https://atcoder.jp/contests/{}", contest_id)
-            };
-            commands::monitor::main(contest_url).await
+        Commands::Login {
+            username,
+            password_stdin,
+            revel_session,
+            remember,
+        } => {
+            commands::login::main(commands::login::Options {
+                username,
+                password_stdin,
+                revel_session,
+                remember,
+            })
+            .await
+        }
+        Commands::Logout => commands::logout::main().await,
+        Commands::Whoami => commands::whoami::main().await,
+        Commands::Diff {
+            old_id,
+            new_id,
+            contest_url,
+        } => {
+            async {
+                let context = resolve_context(contest_url, None)?;
+                commands::diff::main(context, old_id, new_id).await
+            }
+            .await
+        }
+        Commands::Export {
+            contest_url,
+            task,
+            format,
+            output,
+        } => {
+            async {
+                let context = resolve_context(contest_url, task)?;
+                commands::export::main(context, commands::export::Options { format, output }).await
+            }
+            .await
+        }
+        Commands::FetchSources {
+            contest_url,
+            task,
+            accepted_only,
+            latest,
+            output,
+        } => {
+            async {
+                let context = resolve_context(contest_url, task)?;
+                commands::fetch_sources::main(
+                    context,
+                    commands::fetch_sources::Options {
+                        accepted_only,
+                        latest,
+                        output,
+                    },
+                )
+                .await
+            }
+            .await
+        }
+        Commands::WebhookTest => commands::webhook_test::main().await,
+        Commands::Monitor {
+            contest_url,
+            task,
+            ahc,
+            virtual_start,
+            virtual_duration,
+            serve,
+        } => {
+            async {
+                let context = resolve_context(contest_url, task)?;
+                commands::monitor::main(
+                    context,
+                    commands::monitor::Options {
+                        heuristic: ahc,
+                        virtual_start,
+                        virtual_duration,
+                        serve,
+                    },
+                )
+                .await
+            }
+            .await
         }
     };
     std::io::stdout().flush()?;
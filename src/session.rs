@@ -0,0 +1,209 @@
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::ensure;
+use reqwest_cookie_store::{CookieStore, CookieStoreMutex};
+
+use crate::{debug, store, warn};
+
+/// An HTTP client bound to the cookie jar it logs in with.
+#[derive(Clone)]
+pub struct Session {
+    pub client: reqwest::Client,
+    cookie_store: Arc<CookieStoreMutex>,
+}
+
+impl Session {
+    fn with_cookie_store(cookie_store: CookieStore) -> Session {
+        let cookie_store = Arc::new(CookieStoreMutex::new(cookie_store));
+        let client = reqwest::Client::builder()
+            .user_agent("AtCoderJudgeMonitor/0.1")
+            .redirect(reqwest::redirect::Policy::custom(|attempt| {
+                debug!("Redirecting to {}", attempt.url());
+                if attempt.previous().len() > 10 {
+                    attempt.error("too many redirects")
+                } else {
+                    attempt.follow()
+                }
+            }))
+            .cookie_provider(Arc::clone(&cookie_store))
+            .build()
+            .unwrap();
+        Session {
+            client,
+            cookie_store,
+        }
+    }
+
+    /// Creates a session with an empty cookie jar.
+    pub fn new() -> Session {
+        Session::with_cookie_store(CookieStore::default())
+    }
+
+    /// Loads the session saved by `login`.
+    pub fn load() -> Option<Session> {
+        let Ok(cookie_file) = fs_err::File::open(store::get_cookie_path()) else {
+            warn!("Failed to open cookie file");
+            return None;
+        };
+
+        let cookie_store = cookie_store::serde::json::load(std::io::BufReader::new(cookie_file))
+            .unwrap_or_default();
+        Some(Session::with_cookie_store(cookie_store))
+    }
+
+    /// Saves the cookie jar so that later invocations can reuse the session.
+    pub fn save(&self) -> anyhow::Result<()> {
+        let cookie_path = store::get_cookie_path();
+        let cookies = self.cookie_store.lock().unwrap();
+        cookie_store::serde::json::save(&cookies, &mut fs_err::File::create(&cookie_path)?)
+            .map_err(|err| anyhow::anyhow!("Failed to save cookies: {}", err))?;
+        debug!("Cookies saved to {:?}", cookie_path);
+        Ok(())
+    }
+
+    /// Logs in to AtCoder, replacing any cookies in the jar.
+    pub async fn login(&self, username: &str, password: &str) -> anyhow::Result<()> {
+        self.cookie_store.lock().unwrap().clear();
+
+        let login_html: String = self
+            .client
+            .get("https://atcoder.jp/login")
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        let csrf_token = {
+            let login_html = scraper::Html::parse_document(&login_html);
+            login_html
+                .select(&scraper::Selector::parse("input[name=csrf_token]").unwrap())
+                .next()
+                .ok_or_else(|| anyhow::anyhow!("Failed to find CSRF token"))?
+                .value()
+                .attr("value")
+                .unwrap()
+                .to_string()
+        };
+        debug!("CSRF token: {}", csrf_token);
+
+        let mut params = HashMap::new();
+        params.insert("username", username.to_string());
+        params.insert("password", password.to_string());
+        params.insert("csrf_token", csrf_token);
+        let login_result = self
+            .client
+            .post("https://atcoder.jp/login")
+            .header("Referer", "https://atcoder.jp/login")
+            .form(&params)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        ensure!(
+            login_result.contains(format!(r#"var userScreenName = "{}";"#, username).as_str()),
+            "Failed to login"
+        );
+
+        Ok(())
+    }
+
+    /// Imports a `REVEL_SESSION` cookie copied from a browser.
+    pub fn import_revel_session(&self, value: &str) -> anyhow::Result<()> {
+        let value = value.trim();
+        let value = value.strip_prefix("REVEL_SESSION=").unwrap_or(value);
+        ensure!(!value.is_empty(), "REVEL_SESSION is empty");
+
+        // The expiry set by the browser is unknown, so keep the cookie until AtCoder rejects it.
+        let cookie = format!(
+            "REVEL_SESSION={}; Domain=atcoder.jp; Path=/; Secure; HttpOnly; Max-Age={}",
+            value,
+            60 * 60 * 24 * 365
+        );
+        let mut cookies = self.cookie_store.lock().unwrap();
+        cookies.clear();
+        cookies
+            .parse(&cookie, &"https://atcoder.jp/".parse().unwrap())
+            .map_err(|err| anyhow::anyhow!("Invalid REVEL_SESSION: {}", err))?;
+        Ok(())
+    }
+
+    /// Logs in again with the saved credentials and saves the new cookies.
+    // Only dummy submissions are polled with the feature, so the session never expires.
+    #[cfg_attr(feature = "dummy-submissions", allow(dead_code))]
+    pub async fn relogin(&self) -> anyhow::Result<()> {
+        let credentials = crate::credentials::load().await.ok_or_else(|| {
+            anyhow::anyhow!("Session has expired and no credentials are saved, please login again")
+        })?;
+        debug!("Logging in again as {}", credentials.username);
+        self.login(&credentials.username, &credentials.password)
+            .await?;
+        self.save()
+    }
+
+    /// Fetches a page and returns the screen name of the logged-in user, if any.
+    pub async fn current_user(&self) -> anyhow::Result<Option<String>> {
+        let html = self
+            .client
+            .get("https://atcoder.jp/")
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        Ok(parse_js_string(&html, "userScreenName").filter(|name| !name.is_empty()))
+    }
+
+    /// Returns when the `REVEL_SESSION` cookie expires, or `None` if there is no session cookie.
+    pub fn session_expiry(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        let cookies = self.cookie_store.lock().unwrap();
+        let cookie = cookies.get("atcoder.jp", "/", "REVEL_SESSION")?;
+        match cookie.expires {
+            cookie_store::CookieExpiration::AtUtc(expires) => {
+                chrono::DateTime::from_timestamp(expires.unix_timestamp(), 0)
+            }
+            cookie_store::CookieExpiration::SessionEnd => None,
+        }
+    }
+
+    /// Invalidates the session on AtCoder.
+    pub async fn logout(&self) -> anyhow::Result<()> {
+        let html = self
+            .client
+            .get("https://atcoder.jp/")
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        let csrf_token = parse_js_string(&html, "csrfToken")
+            .ok_or_else(|| anyhow::anyhow!("Failed to find CSRF token"))?;
+
+        let mut params = HashMap::new();
+        params.insert("csrf_token", csrf_token);
+        self.client
+            .post("https://atcoder.jp/logout")
+            .header("Referer", "https://atcoder.jp/")
+            .form(&params)
+            .send()
+            .await?
+            .error_for_status()?;
+        self.cookie_store.lock().unwrap().clear();
+
+        Ok(())
+    }
+}
+
+/// Extracts a string variable such as `var userScreenName = "...";` from a page.
+fn parse_js_string(html: &str, name: &str) -> Option<String> {
+    let regex = regex::Regex::new(&format!(r#"var {} = "([^"]*)";"#, name)).unwrap();
+    regex.captures(html).map(|captures| captures[1].to_string())
+}
+
+/// Returns true if the response is the result of being redirected to the login page.
+#[cfg_attr(feature = "dummy-submissions", allow(dead_code))]
+pub fn is_logged_out(response: &reqwest::Response) -> bool {
+    response.url().path() == "/login"
+}
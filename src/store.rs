@@ -1,7 +1,28 @@
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::OnceLock;
 
-use crate::{debug, warn};
+pub const DEFAULT_PROFILE: &str = "default";
+
+static PROFILE: OnceLock<String> = OnceLock::new();
+
+pub fn set_profile(profile: String) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        !profile.is_empty()
+            && profile
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'),
+        "Invalid profile name: {:?}",
+        profile
+    );
+    PROFILE.set(profile).unwrap();
+    Ok(())
+}
+
+pub fn get_profile() -> &'static str {
+    PROFILE
+        .get()
+        .map_or(DEFAULT_PROFILE, |profile| profile.as_str())
+}
 
 pub fn get_config_dir() -> PathBuf {
     let mut path = dirs::config_dir().unwrap();
@@ -9,38 +30,43 @@ pub fn get_config_dir() -> PathBuf {
     path
 }
 
-pub fn get_cookie_path() -> PathBuf {
+/// Returns the directory holding the files of the current profile.
+/// The default profile lives directly in the config directory.
+pub fn get_profile_dir() -> PathBuf {
     let mut path = get_config_dir();
+    let profile = get_profile();
+    if profile != DEFAULT_PROFILE {
+        path.push("profiles");
+        path.push(profile);
+    }
+    path
+}
+
+pub fn get_cookie_path() -> PathBuf {
+    let mut path = get_profile_dir();
     path.push("cookies.json");
     path
 }
 
-pub fn create_config_dir() {
-    let path = get_config_dir();
-    fs_err::create_dir_all(&path).unwrap();
+pub fn get_config_path() -> PathBuf {
+    let mut path = get_profile_dir();
+    path.push("config.json");
+    path
+}
+
+pub fn get_credentials_path() -> PathBuf {
+    let mut path = get_profile_dir();
+    path.push("credentials.bin");
+    path
 }
 
-pub fn create_http_client() -> Option<reqwest::Client> {
-    let Ok(cookie_file) = fs_err::File::open(get_cookie_path()) else {
-        warn!("Failed to open cookie file");
-        return None;
-    };
-
-    let cookie_store =
-        cookie_store::serde::json::load(std::io::BufReader::new(cookie_file)).unwrap_or_default();
-    let cookie_store = reqwest_cookie_store::CookieStoreMutex::new(cookie_store);
-    let agent = reqwest::Client::builder()
-        .user_agent("AtCoderJudgeMonitor/0.1")
-        .redirect(reqwest::redirect::Policy::custom(|attempt| {
-            debug!("Redirecting to {}", attempt.url());
-            if attempt.previous().len() > 10 {
-                attempt.error("too many redirects")
-            } else {
-                attempt.follow()
-            }
-        }))
-        .cookie_provider(Arc::new(cookie_store))
-        .build()
-        .unwrap();
-    Some(agent)
+pub fn get_credentials_key_path() -> PathBuf {
+    let mut path = get_profile_dir();
+    path.push("credentials.key");
+    path
+}
+
+pub fn create_config_dir() {
+    let path = get_profile_dir();
+    fs_err::create_dir_all(&path).unwrap();
 }
@@ -0,0 +1,316 @@
+use std::time::Duration;
+
+use crate::config::{WebhookConfig, WebhookPreset};
+
+/// A change of the verdict of a submission, as sent to webhooks.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct VerdictEvent {
+    pub id: u64,
+    pub contest: String,
+    pub contest_url: String,
+    pub problem: String,
+    pub status: String,
+    /// `None` for a submission that was not seen before.
+    pub previous_status: Option<String>,
+    pub score: u64,
+    pub language: String,
+    pub time: chrono::DateTime<chrono::Utc>,
+    pub execution_time_ms: Option<u64>,
+    pub memory_kib: Option<u64>,
+    pub url: String,
+}
+
+impl VerdictEvent {
+    /// Returns the value of a placeholder such as `{status}` in a payload template.
+    fn placeholder(&self, name: &str) -> Option<String> {
+        Some(match name {
+            "id" => self.id.to_string(),
+            "contest" => self.contest.clone(),
+            "contest_url" => self.contest_url.clone(),
+            "problem" => self.problem.clone(),
+            "status" => self.status.clone(),
+            "previous_status" => self.previous_status.clone().unwrap_or_default(),
+            "score" => self.score.to_string(),
+            "language" => self.language.clone(),
+            "time" => self.time.to_rfc3339(),
+            "execution_time_ms" => self
+                .execution_time_ms
+                .map(|time| time.to_string())
+                .unwrap_or_default(),
+            "memory_kib" => self
+                .memory_kib
+                .map(|memory| memory.to_string())
+                .unwrap_or_default(),
+            "url" => self.url.clone(),
+            _ => return None,
+        })
+    }
+
+    /// Describes the event in a line, e.g. `A - Foo: WJ → AC (100pts, Rust)`.
+    fn summary(&self) -> String {
+        let status = match &self.previous_status {
+            Some(previous_status) => format!("{} → {}", previous_status, self.status),
+            None => self.status.clone(),
+        };
+        format!(
+            "{}: {} ({}pts, {})",
+            self.problem, status, self.score, self.language
+        )
+    }
+
+    /// The color of the verdict for chat embeds.
+    fn color(&self) -> u32 {
+        match self.status.as_str() {
+            "AC" => 0x5cb85c,
+            "WJ" | "WR" | "..." => 0x777777,
+            "IE" => 0xd9534f,
+            _ => 0xf0ad4e,
+        }
+    }
+}
+
+/// Replaces the placeholders in every string of the template.
+fn fill(template: &serde_json::Value, event: &VerdictEvent) -> serde_json::Value {
+    match template {
+        serde_json::Value::String(text) => {
+            let regex = regex::Regex::new(r"\{([a-z_]+)\}").unwrap();
+            let filled = regex.replace_all(text, |captures: &regex::Captures| {
+                event
+                    .placeholder(&captures[1])
+                    .unwrap_or_else(|| captures[0].to_string())
+            });
+            serde_json::Value::String(filled.into_owned())
+        }
+        serde_json::Value::Array(values) => {
+            serde_json::Value::Array(values.iter().map(|value| fill(value, event)).collect())
+        }
+        serde_json::Value::Object(values) => serde_json::Value::Object(
+            values
+                .iter()
+                .map(|(key, value)| (key.clone(), fill(value, event)))
+                .collect(),
+        ),
+        value => value.clone(),
+    }
+}
+
+pub fn payload(config: &WebhookConfig, event: &VerdictEvent) -> serde_json::Value {
+    if let Some(template) = &config.payload {
+        return fill(template, event);
+    }
+    match config.preset {
+        WebhookPreset::Json => serde_json::to_value(event).unwrap_or_default(),
+        WebhookPreset::Discord => serde_json::json!({
+            "embeds": [{
+                "title": format!("{}: {}", event.problem, event.status),
+                "url": event.url,
+                "description": event.summary(),
+                "color": event.color(),
+                "footer": { "text": event.contest },
+                "timestamp": event.time.to_rfc3339(),
+            }],
+        }),
+        WebhookPreset::Slack => serde_json::json!({
+            "text": format!("{} | <{}|{}>", event.contest, event.url, event.summary()),
+        }),
+    }
+}
+
+/// Returns whether a failed request may succeed if sent again.
+fn is_retryable(err: &reqwest::Error) -> bool {
+    match err.status() {
+        Some(status) => {
+            status.is_server_error()
+                || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                || status == reqwest::StatusCode::REQUEST_TIMEOUT
+        }
+        None => err.is_timeout() || err.is_connect() || err.is_request(),
+    }
+}
+
+/// Posts the event to the webhook, retrying with exponential backoff.
+pub async fn send(
+    client: &reqwest::Client,
+    config: &WebhookConfig,
+    event: &VerdictEvent,
+) -> anyhow::Result<()> {
+    let payload = payload(config, event);
+    let mut attempt = 0;
+    loop {
+        let result = client
+            .post(&config.url)
+            .timeout(Duration::from_secs(config.timeout))
+            .json(&payload)
+            .send()
+            .await;
+        let retry_after = result
+            .as_ref()
+            .ok()
+            .and_then(|response| response.headers().get(reqwest::header::RETRY_AFTER))
+            .and_then(|value| value.to_str().ok()?.parse::<u64>().ok());
+        match result.and_then(|response| response.error_for_status()) {
+            Ok(_) => return Ok(()),
+            Err(err) if attempt < config.retries && is_retryable(&err) => {
+                attempt += 1;
+                let delay = retry_after.unwrap_or(1 << attempt.min(6)).min(60);
+                tokio::time::sleep(Duration::from_secs(delay)).await;
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use super::*;
+
+    fn event() -> VerdictEvent {
+        VerdictEvent {
+            id: 1,
+            contest: "abc300".to_string(),
+            contest_url: "https://atcoder.jp/contests/abc300".to_string(),
+            problem: "A - N-choice question".to_string(),
+            status: "AC".to_string(),
+            previous_status: Some("WJ".to_string()),
+            score: 100,
+            language: "Rust (rustc 1.70.0)".to_string(),
+            time: chrono::Utc::now(),
+            execution_time_ms: Some(1),
+            memory_kib: None,
+            url: "https://atcoder.jp/contests/abc300/submissions/1".to_string(),
+        }
+    }
+
+    fn config(url: String, timeout: u64, retries: u32) -> WebhookConfig {
+        WebhookConfig {
+            url,
+            preset: WebhookPreset::Json,
+            payload: Some(serde_json::json!({
+                "text": "{problem}: {previous_status} → {status}",
+                "fields": [{ "score": "{score}", "memory": "{memory_kib}", "unknown": "{foo}" }],
+                "count": 1,
+            })),
+            timeout,
+            retries,
+        }
+    }
+
+    /// Reads a request and returns its body.
+    async fn read_request(stream: &mut tokio::net::TcpStream) -> String {
+        let mut request = Vec::new();
+        let mut buffer = [0; 1024];
+        loop {
+            let read = stream.read(&mut buffer).await.unwrap();
+            assert_ne!(read, 0, "connection closed before the request ended");
+            request.extend_from_slice(&buffer[..read]);
+            let text = String::from_utf8_lossy(&request);
+            let Some(end) = text.find("\r\n\r\n") else {
+                continue;
+            };
+            let length = text[..end]
+                .lines()
+                .find_map(|line| {
+                    let (name, value) = line.split_once(':')?;
+                    name.eq_ignore_ascii_case("content-length")
+                        .then(|| value.trim().parse::<usize>().unwrap())
+                })
+                .unwrap_or(0);
+            if request.len() >= end + 4 + length {
+                return text[end + 4..end + 4 + length].to_string();
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn send_fills_the_template_and_retries_server_errors() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let mut bodies = Vec::new();
+            for status in ["500 Internal Server Error", "200 OK"] {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                bodies.push(read_request(&mut stream).await);
+                let response = format!(
+                    "HTTP/1.1 {}\r\nRetry-After: 0\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                    status
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+            bodies
+        });
+
+        send(&reqwest::Client::new(), &config(url, 10, 1), &event())
+            .await
+            .unwrap();
+
+        let bodies = server.await.unwrap();
+        assert_eq!(bodies.len(), 2);
+        for body in bodies {
+            assert_eq!(
+                serde_json::from_str::<serde_json::Value>(&body).unwrap(),
+                serde_json::json!({
+                    "text": "A - N-choice question: WJ → AC",
+                    "fields": [{ "score": "100", "memory": "", "unknown": "{foo}" }],
+                    "count": 1,
+                })
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn send_gives_up_after_the_retries() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let mut requests = 0;
+            while let Ok((mut stream, _)) = listener.accept().await {
+                read_request(&mut stream).await;
+                requests += 1;
+                let response = "HTTP/1.1 503 Service Unavailable\r\nRetry-After: 0\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+                stream.write_all(response.as_bytes()).await.unwrap();
+                if requests == 3 {
+                    break;
+                }
+            }
+            requests
+        });
+
+        let err = send(&reqwest::Client::new(), &config(url, 10, 2), &event())
+            .await
+            .unwrap_err();
+
+        assert_eq!(server.await.unwrap(), 3);
+        let err = err.downcast::<reqwest::Error>().unwrap();
+        assert_eq!(err.status(), Some(reqwest::StatusCode::SERVICE_UNAVAILABLE));
+    }
+
+    #[tokio::test]
+    async fn send_honours_the_timeout() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        // Reads the request but never responds.
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            read_request(&mut stream).await;
+            tokio::time::sleep(Duration::from_secs(30)).await;
+        });
+
+        let start = Instant::now();
+        let err = send(&reqwest::Client::new(), &config(url, 1, 0), &event())
+            .await
+            .unwrap_err();
+
+        let elapsed = start.elapsed();
+        assert!(
+            (Duration::from_secs(1)..Duration::from_secs(5)).contains(&elapsed),
+            "took {:?}",
+            elapsed
+        );
+        assert!(err.downcast::<reqwest::Error>().unwrap().is_timeout());
+        server.abort();
+    }
+}